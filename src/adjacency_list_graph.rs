@@ -5,10 +5,12 @@
 use super::empty_list_of_lists;
 use super::graph::Graph;
 use super::math_graph;
-use super::path_cost::ArcCost;
+use super::path_cost::{ArcCost, HasArc};
+use super::set_ops::{self, SetOps};
 use super::visitor;
 use super::GraphType;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /**
  * Graph represented as Adjacency list. Directly support
@@ -87,6 +89,13 @@ where
             .iter()
             .map(move |a| (node, a.next, a.weight))
     }
+
+    /**
+     * Return true if the graph contains arc (*src*, *dst*).
+     */
+    pub fn has_arc(&self, src: usize, dst: usize) -> bool {
+        self.lists[src].iter().any(|a| a.next == dst)
+    }
 }
 
 impl<N> Graph<N> for AdjList<N>
@@ -201,6 +210,15 @@ where
     }
 }
 
+impl<N> visitor::SuccessorVisitor<N> for &AdjList<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn successor_visitor<F: FnMut(usize, N)>(&self, node: usize, mut f: F) {
+        self.successor_iterator(node).for_each(|(_, j, w)| f(j, w))
+    }
+}
+
 impl<N> From<math_graph::MathGraph<N>> for AdjList<N>
 where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
@@ -234,9 +252,86 @@ where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
 {
     fn from(adj: AdjList<N>) -> Self {
-        let arcs = math_graph::Arcs::new_weighted(adj.arc_iterator());
+        let arcs = math_graph::Arcs::new(adj.arc_iterator(), adj.gtype);
         let nodes = math_graph::Nodes::new(adj.nodes);
         Self::new(nodes, arcs, adj.gtype)
+            .with_representation(math_graph::Representation::AdjList)
+    }
+}
+
+impl<N> SetOps<N> for AdjList<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn union<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N,
+    {
+        self.combine(other, |a, b| set_ops::union_weight(a, b, &merge))
+    }
+
+    fn intersection<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N,
+    {
+        self.combine(other, |a, b| set_ops::intersection_weight(a, b, &merge))
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        self.combine(other, set_ops::difference_weight)
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine(other, set_ops::symmetric_difference_weight)
+    }
+
+    fn complement(&self) -> Self {
+        let node_count = self.nodes.len();
+        let mut graph = Self::new(node_count, self.gtype);
+        graph.nodes = self.nodes.clone();
+        let mut present = vec![false; node_count];
+        for (i, list) in self.lists.iter().enumerate() {
+            list.iter().for_each(|arc| present[arc.next] = true);
+            for (j, p) in enum_mut! {present} {
+                if !*p && i != j {
+                    graph.make_arc(i, j, N::default());
+                }
+                *p = false;
+            }
+        }
+        graph
+    }
+}
+
+impl<N> AdjList<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    /**
+     * Build a graph with the nodes of *self* and, for each arc in either
+     * graph, the arc weighted by *f* when it returns ```Some```.
+     */
+    fn combine<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(Option<N>, Option<N>) -> Option<N>,
+    {
+        let node_count = self.nodes.len();
+        set_ops::check_compatible((node_count, self.gtype), (other.nodes.len(), other.gtype));
+        let mut arcs = BTreeMap::new();
+        for (i, j, w) in self.arc_iterator() {
+            arcs.entry((i, j)).or_insert((None, None)).0 = Some(w);
+        }
+        for (i, j, w) in other.arc_iterator() {
+            arcs.entry((i, j)).or_insert((None, None)).1 = Some(w);
+        }
+        let mut graph = Self::new(node_count, self.gtype);
+        graph.nodes = self.nodes.clone();
+        for ((i, j), (a, b)) in arcs {
+            if let Some(w) = f(a, b) {
+                graph.make_arc(i, j, w);
+            }
+        }
+        graph
     }
 }
 
@@ -251,6 +346,15 @@ where
     }
 }
 
+impl<N> HasArc for &AdjList<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn has_arc(&self, src: usize, dst: usize) -> bool {
+        AdjList::has_arc(self, src, dst)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 struct AdjArc<N> {
     weight: N,
@@ -270,6 +374,7 @@ where
 mod test {
 
     use super::super::tests;
+    use super::super::geometric::Metric;
     use super::*;
     use crate::visitor::GraphVisitor;
 
@@ -357,7 +462,7 @@ mod test {
 
         let points = [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)];
 
-        graph.update_all_arcs_weight(|i, j, _| tests::euclid_distance(&points[i], &points[j]));
+        graph.update_all_arcs_weight(|i, j, _| Metric::Euclidean.distance(&points[i], &points[j]));
 
         for (i, j, w) in graph.arc_iterator() {
             match (i, j) {
@@ -383,7 +488,7 @@ mod test {
     #[test]
     fn test_node_visitor() {
         let mut graph = make_graph();
-        graph.update_all_nodes_weight(|i, _| (i as f64));
+        graph.update_all_nodes_weight(|i, _| i as f64);
         let mut visit_list: Vec<(usize, f64)> = vec![];
         (&graph).node_visitor(|i, n| visit_list.push((i, n)));
         assert_eq!(vec![(0, 0.0), (1, 1.0), (2, 2.0), (3, 3.0)], visit_list);
@@ -0,0 +1,444 @@
+/*!
+ * Compact, versioned binary serialization of graphs.
+ *
+ * The format is made of:
+ * - a header: the magic bytes `SGRB`, the format version (`u16`),
+ *   the graph type (`u8`) and a tag identifying the weight type (`u8`);
+ * - the node count, as a [LEB128](https://en.wikipedia.org/wiki/LEB128) varint,
+ *   followed by each node weight in raw little endian;
+ * - the arc count, as a varint, followed by the arcs in visiting order.
+ *
+ * Each arc is stored as the zig-zag varint delta of its source from the
+ * previous source, the zig-zag varint delta of its destination from the previous
+ * destination (or from the source when the source changes)
+ * and the raw little endian weight.
+ *
+ * In undirect graphs each arc is stored once. Integers are always written
+ * in little endian, so files are portable across platforms.
+ */
+
+use super::visitor;
+use super::{GetGraphType, Graph, GraphType};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+const MAGIC: &[u8; 4] = b"SGRB";
+
+/**
+ * Current version of the binary format.
+ */
+pub const VERSION: u16 = 1;
+
+/**
+ * Weight types that can be stored in the binary format.
+ */
+pub trait BinaryWeight: Sized {
+    /**
+     * Identify the weight type in the file header.
+     */
+    const TAG: u8;
+
+    fn write_le<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    fn read_le<R: io::Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! binary_weight {
+    ($($t:ty => $tag:expr),*) => {
+        $(
+            impl BinaryWeight for $t {
+                const TAG: u8 = $tag;
+
+                fn write_le<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_le<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut buff = [0; std::mem::size_of::<$t>()];
+                    reader.read_exact(&mut buff)?;
+                    Ok(<$t>::from_le_bytes(buff))
+                }
+            }
+        )*
+    };
+}
+
+binary_weight!(
+    u8 => 1, u16 => 2, u32 => 3, u64 => 4, u128 => 5,
+    i8 => 6, i16 => 7, i32 => 8, i64 => 9, i128 => 10,
+    f32 => 11, f64 => 12
+);
+
+macro_rules! binary_weight_as {
+    ($($t:ty as $repr:ty => $tag:expr),*) => {
+        $(
+            impl BinaryWeight for $t {
+                const TAG: u8 = $tag;
+
+                fn write_le<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+                    (*self as $repr).write_le(writer)
+                }
+
+                fn read_le<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+                    let value = <$repr>::read_le(reader)?;
+                    <$t>::try_from(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+                }
+            }
+        )*
+    };
+}
+
+binary_weight_as!(usize as u64 => 13, isize as i64 => 14);
+
+/**
+ * Describe why a graph could not be decoded.
+ */
+#[derive(Debug)]
+pub enum BinaryError {
+    /**
+     * The underlying reader failed.
+     */
+    Io(io::Error),
+    /**
+     * The input ended before the whole graph was read.
+     */
+    Truncated,
+    /**
+     * The input does not start with the expected magic bytes.
+     */
+    BadMagic,
+    /**
+     * The input was written with an unsupported format version.
+     */
+    UnsupportedVersion(u16),
+    /**
+     * The input stores weights of a different type.
+     */
+    WeightMismatch { expected: u8, found: u8 },
+    /**
+     * The input contains invalid data.
+     */
+    Corrupted(String),
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Truncated => write!(f, "unexpected end of input"),
+            Self::BadMagic => write!(f, "not a simplegraph binary file"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported format version {version}, expected {VERSION}"
+            ),
+            Self::WeightMismatch { expected, found } => write!(
+                f,
+                "weight type mismatch: expected tag {expected}, found tag {found}"
+            ),
+            Self::Corrupted(message) => write!(f, "corrupted input: {message}"),
+        }
+    }
+}
+
+impl Error for BinaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Self::Truncated,
+            _ => Self::Io(err),
+        }
+    }
+}
+
+/**
+ * Write the given [GraphVisitor](visitor::GraphVisitor) into *writer*
+ * using the binary format. Wrap *writer* into a [std::io::BufWriter]
+ * when writing into a file.
+ */
+pub fn write_binary<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy + BinaryWeight,
+{
+    let gtype = g.graph_type();
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&[graph_type_tag(gtype), N::TAG])?;
+
+    let mut status = Ok(());
+    write_varint(writer, g.node_count() as u64)?;
+    g.node_visitor(|_, n| {
+        if status.is_ok() {
+            status = n.write_le(writer);
+        }
+    });
+    std::mem::replace(&mut status, Ok(()))?;
+
+    let keep = |i: usize, j: usize| gtype == GraphType::Direct || i <= j;
+    let mut arc_count = 0;
+    g.arc_visitor(|i, j, _| {
+        if keep(i, j) {
+            arc_count += 1;
+        }
+    });
+    write_varint(writer, arc_count)?;
+
+    let mut prev = (0, 0);
+    g.arc_visitor(|i, j, w| {
+        if status.is_ok() && keep(i, j) {
+            let base = if i == prev.0 { prev.1 } else { i };
+            status = write_varint(writer, zigzag(i, prev.0))
+                .and_then(|_| write_varint(writer, zigzag(j, base)))
+                .and_then(|_| w.write_le(writer));
+            prev = (i, j);
+        }
+    });
+    status
+}
+
+/**
+ * Read a graph written by [write_binary] from *reader*.
+ * Wrap *reader* into a [std::io::BufReader] when reading from a file.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::binary::{read_binary, write_binary};
+ *
+ * let mut graph = AdjList::new_direct(3);
+ * graph.add_new_arc(0, 2, 1.5);
+ * graph.add_new_arc(2, 1, 2.5);
+ *
+ * let mut buff = vec![];
+ * write_binary(&mut buff, &graph).unwrap();
+ * let new_graph: AdjList<f64> = read_binary(buff.as_slice()).unwrap();
+ * assert_eq!(graph, new_graph);
+ *
+ * let err = read_binary::<_, AdjList<f64>, f64>(&buff[..buff.len() - 1]).err().unwrap();
+ * assert_eq!(err.to_string(), "unexpected end of input");
+ * ```
+ */
+pub fn read_binary<R, G, N>(mut reader: R) -> Result<G, BinaryError>
+where
+    R: io::Read,
+    G: Graph<N>,
+    N: num_traits::Num + Copy + BinaryWeight,
+{
+    let mut header = [0; 8];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(BinaryError::BadMagic);
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+    let gtype = match header[6] {
+        0 => GraphType::Direct,
+        1 => GraphType::Undirect,
+        tag => {
+            let message = format!("unknown graph type tag {tag}");
+            return Err(BinaryError::Corrupted(message));
+        }
+    };
+    if header[7] != N::TAG {
+        return Err(BinaryError::WeightMismatch {
+            expected: N::TAG,
+            found: header[7],
+        });
+    }
+
+    let node_count = read_size(&mut reader)?;
+    let mut nodes = Vec::with_capacity(node_count.min(1 << 20));
+    for _ in 0..node_count {
+        nodes.push(N::read_le(&mut reader)?);
+    }
+    let mut graph = G::new(node_count, gtype);
+    graph.update_all_nodes_weight(|i, _| nodes[i]);
+
+    let arc_count = read_size(&mut reader)?;
+    let mut prev = (0, 0);
+    for _ in 0..arc_count {
+        let i = unzigzag(read_varint(&mut reader)?, prev.0, node_count)?;
+        let base = if i == prev.0 { prev.1 } else { i };
+        let j = unzigzag(read_varint(&mut reader)?, base, node_count)?;
+        let w = N::read_le(&mut reader)?;
+        graph.add_new_arc(i, j, w);
+        prev = (i, j);
+    }
+    Ok(graph)
+}
+
+fn graph_type_tag(gtype: GraphType) -> u8 {
+    match gtype {
+        GraphType::Direct => 0,
+        GraphType::Undirect => 1,
+    }
+}
+
+fn zigzag(value: usize, base: usize) -> u64 {
+    let delta = value as i64 - base as i64;
+    ((delta << 1) ^ (delta >> 63)) as u64
+}
+
+fn unzigzag(code: u64, base: usize, node_count: usize) -> Result<usize, BinaryError> {
+    let delta = (code >> 1) as i64 ^ -((code & 1) as i64);
+    match (base as i64).checked_add(delta) {
+        Some(value) if value >= 0 && value < node_count as i64 => Ok(value as usize),
+        Some(value) => {
+            let message = format!("node {value} out of range 0..{node_count}");
+            Err(BinaryError::Corrupted(message))
+        }
+        None => {
+            let message = format!("node offset {delta} from {base} overflows");
+            Err(BinaryError::Corrupted(message))
+        }
+    }
+}
+
+fn write_varint<W: io::Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    let mut buff = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buff[len] = byte;
+            len += 1;
+            break;
+        }
+        buff[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buff[..len])
+}
+
+fn read_varint<R: io::Read>(reader: &mut R) -> Result<u64, BinaryError> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(BinaryError::Corrupted("varint overflow".to_owned()))
+}
+
+fn read_size<R: io::Read>(reader: &mut R) -> Result<usize, BinaryError> {
+    let value = read_varint(reader)?;
+    usize::try_from(value).map_err(|_| BinaryError::Corrupted(format!("size {value} too large")))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::{AdjList, MatrixGraph};
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buff = vec![];
+            write_varint(&mut buff, value).unwrap();
+            assert_eq!(read_varint(&mut buff.as_slice()).unwrap(), value);
+        }
+        let mut buff = vec![];
+        write_varint(&mut buff, 300).unwrap();
+        assert_eq!(buff, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_zigzag() {
+        for (value, base) in [(0, 0), (5, 3), (3, 5), (0, 100), (100, 0)] {
+            assert_eq!(unzigzag(zigzag(value, base), base, 101).unwrap(), value);
+        }
+        assert_eq!(zigzag(4, 5), 1);
+        assert_eq!(zigzag(6, 5), 2);
+        assert!(unzigzag(u64::MAX - 1, 5, 10).is_err());
+        assert!(unzigzag(u64::MAX, 5, 10).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = AdjList::new_undirect(5);
+        graph.update_all_nodes_weight(|i, _| i as i32 - 2);
+        graph.add_new_arc(0, 4, 10);
+        graph.add_new_arc(4, 1, -20);
+        graph.add_new_arc(2, 3, 30);
+
+        let mut buff = vec![];
+        write_binary(&mut buff, &graph).unwrap();
+        let new_graph: AdjList<i32> = read_binary(buff.as_slice()).unwrap();
+        assert_eq!(graph, new_graph);
+
+        let matrix: MatrixGraph<i32> = read_binary(buff.as_slice()).unwrap();
+        let mut matrix_buff = vec![];
+        write_binary(&mut matrix_buff, &matrix).unwrap();
+        assert_eq!(buff, matrix_buff);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let mut graph = MatrixGraph::new_direct(3);
+        graph.add_new_arc(0, 1, 1u64);
+        graph.add_new_arc(2, 1, 2u64);
+        let mut buff = vec![];
+        write_binary(&mut buff, &graph).unwrap();
+
+        let err = read_binary::<_, AdjList<u32>, u32>(buff.as_slice()).unwrap_err();
+        assert!(matches!(
+            err,
+            BinaryError::WeightMismatch {
+                expected: 3,
+                found: 4
+            }
+        ));
+
+        for len in 0..buff.len() {
+            let err = read_binary::<_, AdjList<u64>, u64>(&buff[..len]).unwrap_err();
+            assert!(matches!(err, BinaryError::Truncated), "{len}: {err}");
+        }
+
+        let mut bad_version = buff.clone();
+        bad_version[4] = 2;
+        let err = read_binary::<_, AdjList<u64>, u64>(bad_version.as_slice()).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported format version 2, expected 1");
+
+        let mut bad_magic = buff.clone();
+        bad_magic[0] = b'X';
+        let err = read_binary::<_, AdjList<u64>, u64>(bad_magic.as_slice()).unwrap_err();
+        assert!(matches!(err, BinaryError::BadMagic));
+
+        let mut bad_node = buff.clone();
+        let first_arc = 8 + 1 + 3 * 8 + 1;
+        bad_node[first_arc] = 20;
+        let err = read_binary::<_, AdjList<u64>, u64>(bad_node.as_slice()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "corrupted input: node 10 out of range 0..3"
+        );
+
+        // offset i64::MAX from node 2 for the destination of the second arc
+        let mut overflow = buff;
+        let mut code = vec![];
+        write_varint(&mut code, u64::MAX - 1).unwrap();
+        let second_dst = first_arc + 2 + 8 + 1;
+        overflow.splice(second_dst..second_dst + 1, code);
+        let err = read_binary::<_, AdjList<u64>, u64>(overflow.as_slice()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "corrupted input: node offset 9223372036854775807 from 2 overflows"
+        );
+    }
+}
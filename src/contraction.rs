@@ -0,0 +1,218 @@
+/*!
+ * [Node contraction](https://en.wikipedia.org/wiki/Vertex_identification):
+ * merge groups of nodes into single nodes, redirecting their arcs.
+ *
+ * Node weights within a group are combined by the node closure, parallel
+ * arcs between two groups are combined by the arc closure, in both cases
+ * folding weights in node, then arc, visit order. Arcs between nodes of the
+ * same group are dropped. Contraction returns the new graph and the
+ * old-to-new index map: old node *i* becomes node *map\[i\]*.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::contraction::contract_nodes;
+ *
+ * let mut graph = AdjList::<u32>::new_direct(4);
+ * graph.update_all_nodes_weight(|_, _| 1);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 2, 2);
+ * graph.add_new_arc(0, 3, 3);
+ * graph.add_new_arc(3, 2, 4);
+ *
+ * let (contracted, map): (AdjList<u32>, _) =
+ *     contract_nodes(&graph, &[1, 3], |a, b| a + b, |a, b| a + b);
+ * assert_eq!(map, vec![0, 1, 2, 1]);
+ * let arcs: Vec<_> = contracted.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 4), (1, 2, 6)]);
+ * let nodes: Vec<_> = contracted.node_iterator().collect();
+ * assert_eq!(nodes, vec![(0, 1), (1, 2), (2, 1)]);
+ * ```
+ */
+
+use super::visitor::GraphVisitor;
+use super::{GetGraphType, Graph, GraphType};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+/**
+ * Contract each group of *partition* into a single node: *partition\[i\]*
+ * is the group label of node *i*. Labels can be any value, groups are
+ * numbered in order of first appearance, so the relative order of the
+ * groups' first node is preserved. Return the contracted graph and the
+ * old-to-new index map. A partition computed by a strongly connected
+ * component algorithm gives the condensation of a direct graph.
+ *
+ * # Panics
+ * Panics if *partition* length is different from the node count.
+ */
+pub fn contract<GA, G, N, FN, FA>(
+    graph: GA,
+    partition: &[usize],
+    node_merge: FN,
+    arc_merge: FA,
+) -> (G, Vec<usize>)
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(N, N) -> N,
+{
+    assert_eq!(
+        partition.len(),
+        graph.node_count(),
+        "partition size differs from node count"
+    );
+    let mut labels = HashMap::new();
+    let map: Vec<usize> = partition
+        .iter()
+        .map(|label| {
+            let next = labels.len();
+            *labels.entry(*label).or_insert(next)
+        })
+        .collect();
+    let count = labels.len();
+
+    let mut nodes: Vec<Option<N>> = vec![None; count];
+    graph.node_visitor(|i, w| {
+        let node = &mut nodes[map[i]];
+        *node = Some(match node {
+            Some(curr) => node_merge(*curr, w),
+            None => w,
+        });
+    });
+
+    let gtype = graph.graph_type();
+    let mut arcs = BTreeMap::new();
+    graph.arc_visitor(|i, j, w| {
+        let (src, dst) = (map[i], map[j]);
+        if src == dst || (gtype == GraphType::Undirect && i > j) {
+            return;
+        }
+        let key = match gtype {
+            GraphType::Direct => (src, dst),
+            GraphType::Undirect => (src.min(dst), src.max(dst)),
+        };
+        match arcs.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(w);
+            }
+            Entry::Occupied(mut entry) => {
+                let curr = entry.get_mut();
+                *curr = arc_merge(*curr, w);
+            }
+        }
+    });
+
+    let mut contracted = G::new(count, gtype);
+    contracted.update_all_nodes_weight(|i, _| nodes[i].unwrap());
+    for ((src, dst), w) in arcs {
+        contracted.add_new_arc(src, dst, w);
+    }
+    (contracted, map)
+}
+
+/**
+ * Contract *nodes* into a single node, which takes the position
+ * of the smallest of them, every other node keeps its relative order.
+ * Return the contracted graph and the old-to-new index map.
+ * See [contract] for the details.
+ *
+ * # Panics
+ * Panics if *nodes* is empty or contains an index out of range.
+ */
+pub fn contract_nodes<GA, G, N, FN, FA>(
+    graph: GA,
+    nodes: &[usize],
+    node_merge: FN,
+    arc_merge: FA,
+) -> (G, Vec<usize>)
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(N, N) -> N,
+{
+    let target = *nodes.iter().min().expect("no nodes to contract");
+    let mut partition: Vec<usize> = (0..graph.node_count()).collect();
+    for node in nodes {
+        partition[*node] = target;
+    }
+    contract(graph, &partition, node_merge, arc_merge)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::families::{complete, cycle};
+    use crate::{AdjList, MatrixGraph};
+
+    #[test]
+    fn test_contract_partition() {
+        // two triangles connected by two arcs
+        let mut graph = AdjList::<u32>::new_undirect(6);
+        graph.update_all_nodes_weight(|i, _| i as u32);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 1);
+        graph.add_new_arc(2, 0, 1);
+        graph.add_new_arc(3, 4, 1);
+        graph.add_new_arc(4, 5, 1);
+        graph.add_new_arc(5, 3, 1);
+        graph.add_new_arc(0, 3, 5);
+        graph.add_new_arc(5, 2, 7);
+
+        let partition = [9, 9, 9, 4, 4, 4];
+        let (contracted, map): (MatrixGraph<u32>, _) =
+            contract(&graph, &partition, |a, b| a + b, |a, b| a + b);
+        assert_eq!(map, vec![0, 0, 0, 1, 1, 1]);
+        let nodes: Vec<_> = contracted.node_iterator().collect();
+        assert_eq!(nodes, vec![(0, 3), (1, 12)]);
+        let arcs: Vec<_> = contracted.arc_iterator().collect();
+        assert_eq!(arcs, vec![(0, 1, 12), (1, 0, 12)]);
+    }
+
+    #[test]
+    fn test_contract_direct() {
+        let mut graph = AdjList::<u32>::new_direct(4);
+        graph.add_new_arc(0, 1, 3);
+        graph.add_new_arc(1, 0, 4);
+        graph.add_new_arc(2, 1, 5);
+        graph.add_new_arc(2, 3, 6);
+        graph.add_new_arc(3, 2, 2);
+
+        let (contracted, map): (AdjList<u32>, _) =
+            contract(&graph, &[1, 0, 1, 0], |a, _| a, |a, b| a.max(b));
+        assert_eq!(map, vec![0, 1, 0, 1]);
+        let mut arcs: Vec<_> = contracted.arc_iterator().collect();
+        arcs.sort_unstable();
+        assert_eq!(arcs, vec![(0, 1, 6), (1, 0, 4)]);
+    }
+
+    #[test]
+    fn test_contract_nodes() {
+        let graph: AdjList<u32> = cycle(6, GraphType::Undirect);
+        let (contracted, map): (AdjList<u32>, _) =
+            contract_nodes(&graph, &[4, 1], |a, b| a + b, |a, b| a + b);
+        assert_eq!(map, vec![0, 1, 2, 3, 1, 4]);
+        assert_eq!((&contracted).node_count(), 5);
+        // arcs (1, 2), (3, 4), (4, 5) and (0, 1) now touch node 1
+        let mut degree = vec![0; 5];
+        (&contracted).arc_visitor(|i, _, _| degree[i] += 1);
+        assert_eq!(degree, vec![2, 4, 2, 2, 2]);
+
+        let graph: MatrixGraph<u32> = complete(5, GraphType::Direct);
+        let (contracted, _): (MatrixGraph<u32>, _) =
+            contract_nodes(&graph, &[0, 1, 2, 3, 4], |a, _| a, |a, _| a);
+        assert_eq!((&contracted).node_count(), 1);
+        assert_eq!((&contracted).arc_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "partition size differs from node count")]
+    fn test_wrong_partition() {
+        let graph = AdjList::<u32>::new_direct(3);
+        let _: (AdjList<u32>, _) = contract(&graph, &[0, 1], |a, _| a, |a, _| a);
+    }
+}
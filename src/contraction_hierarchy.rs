@@ -0,0 +1,654 @@
+/*!
+ * [Contraction hierarchies](https://en.wikipedia.org/wiki/Contraction_hierarchies)
+ * for fast repeated shortest path queries on a static graph with
+ * non-negative arc weights.
+ *
+ * Preprocessing contracts the nodes one at a time, in order of importance,
+ * adding a shortcut arc whenever the only shortest path between two
+ * neighbours of the contracted node goes through it. A query then runs a
+ * bidirectional Dijkstra search that only climbs the hierarchy, settling a
+ * handful of nodes, and unpacks the shortcuts of the path found into the
+ * original nodes.
+ *
+ * Preprocessing is expensive, so the resulting [ContractionHierarchy]
+ * can be stored with [Serde](https://serde.rs) and loaded back later.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::contraction_hierarchy::ContractionHierarchy;
+ *
+ * let mut graph = AdjList::<u32>::new_direct(4);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 3, 1);
+ * graph.add_new_arc(0, 2, 1);
+ * graph.add_new_arc(2, 3, 2);
+ * graph.add_new_arc(0, 3, 4);
+ *
+ * let hierarchy = ContractionHierarchy::new(&graph);
+ * assert_eq!(hierarchy.query(0, 3), Some((vec![0, 1, 3], 2)));
+ * assert_eq!(hierarchy.query(3, 0), None);
+ * ```
+ */
+
+use super::shortest_path::Entry;
+use super::visitor::GraphVisitor;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/**
+ * Maximum number of nodes settled by a witness search. A search that
+ * gives up early only costs an unnecessary shortcut.
+ */
+const WITNESS_LIMIT: usize = 128;
+
+/**
+ * Graph augmented with shortcuts and the contraction order of its nodes.
+ * Each arc is stored at its lower ranked end: arcs going up the hierarchy
+ * from their source, arcs coming down the hierarchy at their destination.
+ *
+ * Deserialized hierarchies are validated: inconsistent data makes
+ * deserialization fail with an [InvalidHierarchy] message instead of
+ * panicking or returning wrong paths at query time.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "UncheckedHierarchy<N>")]
+pub struct ContractionHierarchy<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    rank: Vec<usize>,
+    up: Vec<Vec<HierarchyArc<N>>>,
+    down: Vec<Vec<HierarchyArc<N>>>,
+}
+
+#[derive(Deserialize)]
+struct UncheckedHierarchy<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    rank: Vec<usize>,
+    up: Vec<Vec<HierarchyArc<N>>>,
+    down: Vec<Vec<HierarchyArc<N>>>,
+}
+
+impl<N> TryFrom<UncheckedHierarchy<N>> for ContractionHierarchy<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    type Error = InvalidHierarchy;
+
+    fn try_from(h: UncheckedHierarchy<N>) -> Result<Self, Self::Error> {
+        let hierarchy = Self {
+            rank: h.rank,
+            up: h.up,
+            down: h.down,
+        };
+        hierarchy.validate()?;
+        Ok(hierarchy)
+    }
+}
+
+/**
+ * Arc of the hierarchy: *node* is the other end of the arc, *middle*
+ * the contracted node a shortcut stands for.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+struct HierarchyArc<N> {
+    node: usize,
+    weight: N,
+    middle: Option<usize>,
+}
+
+/**
+ * Reason a deserialized [ContractionHierarchy] was rejected.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidHierarchy {
+    /**
+     * Ranks and arc lists have different lengths.
+     */
+    LengthMismatch,
+    /**
+     * The ranks are not a permutation of the nodes.
+     */
+    InvalidRank,
+    /**
+     * An arc refers to a node out of range or does not
+     * go from a lower to a higher ranked node.
+     */
+    InvalidArc { src: usize, dst: usize },
+    /**
+     * A shortcut middle node is out of range, is not ranked below
+     * both ends or does not connect them.
+     */
+    InvalidShortcut {
+        src: usize,
+        dst: usize,
+        middle: usize,
+    },
+}
+
+impl fmt::Display for InvalidHierarchy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch => write!(f, "ranks and arc lists have different lengths"),
+            Self::InvalidRank => write!(f, "ranks are not a permutation of the nodes"),
+            Self::InvalidArc { src, dst } => write!(f, "invalid arc ({src}, {dst})"),
+            Self::InvalidShortcut { src, dst, middle } => {
+                write!(f, "invalid shortcut ({src}, {dst}) through {middle}")
+            }
+        }
+    }
+}
+
+impl Error for InvalidHierarchy {}
+
+/**
+ * Arcs of the nodes still to contract: other end, weight and middle node.
+ */
+type Remaining<N> = Vec<BTreeMap<usize, (N, Option<usize>)>>;
+
+impl<N> ContractionHierarchy<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    /**
+     * Contract all the nodes of *graph*. Self loops are dropped and
+     * only the lightest of parallel arcs is kept.
+     */
+    pub fn new<G: GraphVisitor<N>>(graph: G) -> Self {
+        let count = graph.node_count();
+        let mut out: Remaining<N> = vec![BTreeMap::new(); count];
+        let mut inc: Remaining<N> = vec![BTreeMap::new(); count];
+        graph.arc_visitor(|i, j, w| {
+            if i != j {
+                insert_min(&mut out[i], j, w, None);
+                insert_min(&mut inc[j], i, w, None);
+            }
+        });
+
+        let mut hierarchy = Self {
+            rank: vec![0; count],
+            up: vec![vec![]; count],
+            down: vec![vec![]; count],
+        };
+        let mut deleted = vec![0; count];
+        let mut queue: BinaryHeap<_> = (0..count)
+            .map(|node| {
+                let shortcuts = find_shortcuts(node, &out, &inc).len();
+                let degree = out[node].len() + inc[node].len();
+                Reverse((priority(shortcuts, 0, degree), node))
+            })
+            .collect();
+
+        // lazy updates: contract the node only if it still comes first
+        let mut next_rank = 0;
+        while let Some(Reverse((_, node))) = queue.pop() {
+            let shortcuts = find_shortcuts(node, &out, &inc);
+            let degree = out[node].len() + inc[node].len();
+            let curr = priority(shortcuts.len(), deleted[node], degree);
+            if let Some(Reverse((next, _))) = queue.peek() {
+                if curr > *next {
+                    queue.push(Reverse((curr, node)));
+                    continue;
+                }
+            }
+
+            hierarchy.rank[node] = next_rank;
+            next_rank += 1;
+            for (src, (weight, middle)) in std::mem::take(&mut inc[node]) {
+                out[src].remove(&node);
+                deleted[src] += 1;
+                hierarchy.down[node].push(HierarchyArc {
+                    node: src,
+                    weight,
+                    middle,
+                });
+            }
+            for (dst, (weight, middle)) in std::mem::take(&mut out[node]) {
+                inc[dst].remove(&node);
+                deleted[dst] += 1;
+                hierarchy.up[node].push(HierarchyArc {
+                    node: dst,
+                    weight,
+                    middle,
+                });
+            }
+            for (src, dst, weight) in shortcuts {
+                insert_min(&mut out[src], dst, weight, Some(node));
+                insert_min(&mut inc[dst], src, weight, Some(node));
+            }
+        }
+        hierarchy
+    }
+
+    /**
+     * Find the shortest path from *src* to *dst*, or ```None```
+     * if *dst* is not reachable from *src*.
+     */
+    pub fn query(&self, src: usize, dst: usize) -> Option<(Vec<usize>, N)> {
+        let mut forward = Search::new(src);
+        let mut backward = Search::new(dst);
+        let mut best: Option<(N, usize)> = None;
+
+        loop {
+            let bound = best.map(|(cost, _)| cost);
+            let go_forward = match (forward.top(bound), backward.top(bound)) {
+                (None, None) => break,
+                (Some(top_f), Some(top_b)) => top_f <= top_b,
+                (top_f, _) => top_f.is_some(),
+            };
+            let (search, other, arcs) = if go_forward {
+                (&mut forward, &backward, &self.up)
+            } else {
+                (&mut backward, &forward, &self.down)
+            };
+            let Some((node, cost)) = search.settle() else {
+                continue;
+            };
+            if let Some((rest, _)) = other.dist.get(&node) {
+                let total = cost + *rest;
+                if best.is_none_or(|(curr, _)| total < curr) {
+                    best = Some((total, node));
+                }
+            }
+            for arc in &arcs[node] {
+                search.relax(node, arc.node, cost + arc.weight);
+            }
+        }
+
+        let (cost, meet) = best?;
+        let mut hops = forward.path(meet);
+        hops.reverse();
+        hops.extend(backward.path(meet).into_iter().skip(1));
+        let mut path = vec![src];
+        for hop in hops.windows(2) {
+            self.unpack(hop[0], hop[1], &mut path);
+        }
+        Some((path, cost))
+    }
+
+    /**
+     * Return the number of nodes in the hierarchy.
+     */
+    pub fn node_count(&self) -> usize {
+        self.rank.len()
+    }
+
+    /**
+     * Return the number of shortcuts added by the preprocessing.
+     */
+    pub fn shortcut_count(&self) -> usize {
+        self.up
+            .iter()
+            .chain(&self.down)
+            .flatten()
+            .filter(|arc| arc.middle.is_some())
+            .count()
+    }
+}
+
+impl<N> ContractionHierarchy<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    /**
+     * Append to *path* the original nodes after *src* on the arc to *dst*.
+     */
+    fn unpack(&self, src: usize, dst: usize, path: &mut Vec<usize>) {
+        let mut stack = vec![(src, dst)];
+        while let Some((src, dst)) = stack.pop() {
+            match self.find_arc(src, dst).and_then(|arc| arc.middle) {
+                Some(middle) => {
+                    stack.push((middle, dst));
+                    stack.push((src, middle));
+                }
+                None => path.push(dst),
+            }
+        }
+    }
+
+    fn find_arc(&self, src: usize, dst: usize) -> Option<&HierarchyArc<N>> {
+        if self.rank[src] < self.rank[dst] {
+            self.up[src].iter().find(|arc| arc.node == dst)
+        } else {
+            self.down[dst].iter().find(|arc| arc.node == src)
+        }
+    }
+
+    fn validate(&self) -> Result<(), InvalidHierarchy> {
+        let count = self.rank.len();
+        if self.up.len() != count || self.down.len() != count {
+            return Err(InvalidHierarchy::LengthMismatch);
+        }
+        let mut seen = vec![false; count];
+        for rank in &self.rank {
+            if *rank >= count || std::mem::replace(&mut seen[*rank], true) {
+                return Err(InvalidHierarchy::InvalidRank);
+            }
+        }
+
+        let arcs = self
+            .up
+            .iter()
+            .enumerate()
+            .flat_map(|(src, arcs)| arcs.iter().map(move |arc| (src, arc.node, arc.middle)));
+        let arcs = arcs.chain(
+            self.down
+                .iter()
+                .enumerate()
+                .flat_map(|(dst, arcs)| arcs.iter().map(move |arc| (arc.node, dst, arc.middle))),
+        );
+        for (src, dst, middle) in arcs {
+            // an arc stored at its higher ranked end is not found
+            if src >= count || dst >= count || src == dst || self.find_arc(src, dst).is_none() {
+                return Err(InvalidHierarchy::InvalidArc { src, dst });
+            }
+            if let Some(middle) = middle {
+                let valid = middle < count
+                    && self.rank[middle] < self.rank[src].min(self.rank[dst])
+                    && self.find_arc(src, middle).is_some()
+                    && self.find_arc(middle, dst).is_some();
+                if !valid {
+                    return Err(InvalidHierarchy::InvalidShortcut { src, dst, middle });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Contraction priority of a node, lower first: the edge difference,
+ * the shortcuts added minus the arcs removed, plus the neighbours
+ * already contracted, to spread contraction evenly over the graph.
+ */
+fn priority(shortcuts: usize, deleted: usize, degree: usize) -> isize {
+    (shortcuts + deleted) as isize - degree as isize
+}
+
+fn insert_min<N: PartialOrd>(
+    arcs: &mut BTreeMap<usize, (N, Option<usize>)>,
+    node: usize,
+    weight: N,
+    middle: Option<usize>,
+) {
+    match arcs.get(&node) {
+        Some((curr, _)) if *curr <= weight => {}
+        _ => {
+            arcs.insert(node, (weight, middle));
+        }
+    }
+}
+
+/**
+ * Shortcuts needed to contract *node*: for each pair of remaining
+ * neighbours, the arc from one to the other through *node* unless a
+ * witness path, not longer and avoiding *node*, exists.
+ */
+fn find_shortcuts<N>(node: usize, out: &Remaining<N>, inc: &Remaining<N>) -> Vec<(usize, usize, N)>
+where
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    let mut shortcuts = vec![];
+    for (src, (first, _)) in &inc[node] {
+        let costs: Vec<_> = out[node]
+            .iter()
+            .filter(|(dst, _)| *dst != src)
+            .map(|(dst, (second, _))| (*dst, *first + *second))
+            .collect();
+        let Some(limit) = costs
+            .iter()
+            .map(|(_, cost)| *cost)
+            .reduce(|a, b| if b > a { b } else { a })
+        else {
+            continue;
+        };
+        let witness = witness_search(*src, node, limit, out);
+        for (dst, cost) in costs {
+            if witness.get(&dst).is_none_or(|dist| *dist > cost) {
+                shortcuts.push((*src, dst, cost));
+            }
+        }
+    }
+    shortcuts
+}
+
+/**
+ * Costs of the paths from *src* avoiding *avoid*, found by a Dijkstra
+ * search that stops after *limit* or [WITNESS_LIMIT] settled nodes.
+ */
+fn witness_search<N>(src: usize, avoid: usize, limit: N, out: &Remaining<N>) -> HashMap<usize, N>
+where
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    let mut dist = HashMap::from([(src, N::zero())]);
+    let mut queue = BinaryHeap::from([Entry {
+        cost: N::zero(),
+        node: src,
+    }]);
+    let mut settled = 0;
+    while let Some(Entry { cost, node }) = queue.pop() {
+        if dist.get(&node).is_some_and(|curr| *curr < cost) {
+            continue;
+        }
+        if cost > limit || settled == WITNESS_LIMIT {
+            break;
+        }
+        settled += 1;
+        for (next, (weight, _)) in &out[node] {
+            let cost = cost + *weight;
+            if *next != avoid && dist.get(next).is_none_or(|curr| cost < *curr) {
+                dist.insert(*next, cost);
+                queue.push(Entry { cost, node: *next });
+            }
+        }
+    }
+    dist
+}
+
+/**
+ * State of one direction of a query: costs and previous
+ * nodes of the reached nodes, kept sparse since a query
+ * only touches a tiny part of the hierarchy.
+ */
+struct Search<N> {
+    dist: HashMap<usize, (N, usize)>,
+    queue: BinaryHeap<Entry<N>>,
+}
+
+impl<N> Search<N>
+where
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    fn new(root: usize) -> Self {
+        Self {
+            dist: HashMap::from([(root, (N::zero(), usize::MAX))]),
+            queue: BinaryHeap::from([Entry {
+                cost: N::zero(),
+                node: root,
+            }]),
+        }
+    }
+
+    /**
+     * Minimum cost in the queue, ```None``` if the queue is empty or
+     * the search can no longer improve on the *best* path found.
+     */
+    fn top(&self, best: Option<N>) -> Option<N> {
+        let top = self.queue.peek()?.cost;
+        best.is_none_or(|best| top < best).then_some(top)
+    }
+
+    /**
+     * Pop the next node, ```None``` if its queue entry is stale.
+     */
+    fn settle(&mut self) -> Option<(usize, N)> {
+        let Entry { cost, node } = self.queue.pop()?;
+        let (curr, _) = self.dist[&node];
+        (curr >= cost).then_some((node, cost))
+    }
+
+    fn relax(&mut self, node: usize, next: usize, cost: N) {
+        if self.dist.get(&next).is_none_or(|(curr, _)| cost < *curr) {
+            self.dist.insert(next, (cost, node));
+            self.queue.push(Entry { cost, node: next });
+        }
+    }
+
+    /**
+     * Nodes from *node* back to the root of the search.
+     */
+    fn path(&self, mut node: usize) -> Vec<usize> {
+        let mut path = vec![node];
+        loop {
+            let (_, prev) = self.dist[&node];
+            if prev == usize::MAX {
+                return path;
+            }
+            node = prev;
+            path.push(node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::families::grid_2d;
+    use crate::path_cost::{is_simple_path, path_cost};
+    use crate::shortest_path::dijkstra;
+    use crate::{AdjList, Graph, GraphType};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_grid(side: usize, gtype: GraphType, rng: &mut StdRng) -> AdjList<u64> {
+        let (mut graph, _): (AdjList<u64>, _) = grid_2d(side, side, gtype);
+        let mut weights = HashMap::new();
+        (&graph).arc_visitor(|i, j, _| {
+            weights
+                .entry((i.min(j), i.max(j)))
+                .or_insert_with(|| rng.gen_range(1..1000u64));
+        });
+        graph.update_all_arcs_weight(|i, j, _| weights[&(i.min(j), i.max(j))]);
+        graph
+    }
+
+    #[test]
+    fn test_query() {
+        let mut graph = AdjList::<u32>::new_direct(7);
+        graph.add_new_arc(0, 1, 2);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 2);
+        graph.add_new_arc(0, 4, 1);
+        graph.add_new_arc(4, 3, 9);
+        graph.add_new_arc(3, 0, 1);
+        graph.add_new_arc(2, 2, 1);
+        graph.add_new_arc(1, 2, 5);
+        let hierarchy = ContractionHierarchy::new(&graph);
+        assert_eq!(hierarchy.node_count(), 7);
+        for src in 0..7 {
+            for dst in 0..7 {
+                let found = hierarchy.query(src, dst);
+                let expect = dijkstra(&graph, src, dst);
+                assert_eq!(found, expect, "{src} -> {dst}");
+            }
+        }
+        assert_eq!(hierarchy.query(5, 5), Some((vec![5], 0)));
+        assert_eq!(hierarchy.query(5, 6), None);
+    }
+
+    #[test]
+    fn test_query_grid() {
+        let mut rng = StdRng::seed_from_u64(50);
+        for gtype in [GraphType::Undirect, GraphType::Direct] {
+            let graph = random_grid(15, gtype, &mut rng);
+            let hierarchy = ContractionHierarchy::new(&graph);
+            assert!(hierarchy.shortcut_count() > 0);
+            for _ in 0..200 {
+                let src = rng.gen_range(0..225);
+                let dst = rng.gen_range(0..225);
+                let (path, cost) = hierarchy.query(src, dst).unwrap();
+                let (_, expect) = dijkstra(&graph, src, dst).unwrap();
+                assert_eq!(cost, expect);
+                assert_eq!(path.first(), Some(&src));
+                assert_eq!(path.last(), Some(&dst));
+                assert!(is_simple_path(&graph, &path));
+                assert_eq!(path_cost(&graph, &path), Ok(cost));
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let graph = random_grid(6, GraphType::Direct, &mut rng);
+        let hierarchy = ContractionHierarchy::new(&graph);
+        let json = serde_json::to_string(&hierarchy).unwrap();
+        let loaded: ContractionHierarchy<u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, hierarchy);
+        assert_eq!(loaded.query(0, 35), hierarchy.query(0, 35));
+    }
+
+    #[test]
+    fn test_invalid_hierarchy() {
+        let load = |json: &str| {
+            serde_json::from_str::<ContractionHierarchy<u32>>(json)
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        let arc = |node: usize, middle: Option<usize>| HierarchyArc {
+            node,
+            weight: 1,
+            middle,
+        };
+        let json =
+            |rank: &[usize], up: &[Vec<HierarchyArc<u32>>], down: &[Vec<HierarchyArc<u32>>]| {
+                format!(
+                    r#"{{"rank":{},"up":{},"down":{}}}"#,
+                    serde_json::to_string(rank).unwrap(),
+                    serde_json::to_string(up).unwrap(),
+                    serde_json::to_string(down).unwrap()
+                )
+            };
+
+        let valid = json(
+            &[0, 2, 1],
+            &[vec![arc(1, None)], vec![], vec![arc(1, Some(0))]],
+            &[vec![arc(2, None)], vec![], vec![]],
+        );
+        assert!(serde_json::from_str::<ContractionHierarchy<u32>>(&valid).is_ok());
+
+        let error = load(&json(&[0, 1], &[vec![]], &[vec![], vec![]]));
+        assert!(error.contains("different lengths"), "{error}");
+        let error = load(&json(&[0, 0], &[vec![], vec![]], &[vec![], vec![]]));
+        assert!(error.contains("permutation"), "{error}");
+        let error = load(&json(
+            &[0, 1],
+            &[vec![arc(2, None)], vec![]],
+            &[vec![], vec![]],
+        ));
+        assert!(error.contains("invalid arc (0, 2)"), "{error}");
+        let error = load(&json(
+            &[0, 1],
+            &[vec![], vec![arc(0, None)]],
+            &[vec![], vec![]],
+        ));
+        assert!(error.contains("invalid arc (1, 0)"), "{error}");
+        let error = load(&json(
+            &[0, 1, 2],
+            &[vec![arc(1, Some(2))], vec![], vec![]],
+            &[vec![], vec![], vec![]],
+        ));
+        assert!(
+            error.contains("invalid shortcut (0, 1) through 2"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,338 @@
+/*!
+ * Read and write graphs in the
+ * [DIMACS](http://www.diag.uniroma1.it/challenge9/format.shtml) challenge
+ * formats: shortest path (`p sp`) and maximum flow (`p max`) problems.
+ * DIMACS numbers nodes starting from 1 while this crate starts from 0:
+ * node indexes are converted while reading and writing.
+ * DIMACS graphs are always direct.
+ */
+
+use super::read_error::ReadError;
+use super::visitor;
+use super::{Graph, GraphType};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/**
+ * A maximum flow problem instance: the capacity graph
+ * and the source and sink nodes' indexes.
+ */
+#[derive(Debug, Clone)]
+pub struct MaxFlow<G> {
+    pub graph: G,
+    pub source: usize,
+    pub sink: usize,
+}
+
+/**
+ * Read a shortest path problem (`p sp`) from *reader*.
+ *
+ * ```
+ * use simplegraph::AdjList;
+ * use simplegraph::dimacs::read_shortest_path;
+ *
+ * let source = "c tiny instance\np sp 3 2\na 1 2 10\na 2 3 5\n";
+ * let graph: AdjList<u32> = read_shortest_path(source.as_bytes()).unwrap();
+ * let arcs: Vec<_> = graph.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 10), (1, 2, 5)]);
+ * ```
+ */
+pub fn read_shortest_path<R, G, N>(reader: R) -> Result<G, ReadError>
+where
+    R: io::BufRead,
+    G: Graph<N>,
+    N: num_traits::Num + FromStr,
+{
+    let (graph, terminals, _) = read_problem(reader, "sp")?;
+    if let Some((line, _)) = terminals.first() {
+        return Err(ReadError::syntax(
+            *line,
+            "node designators are not allowed in shortest path problems",
+        ));
+    }
+    Ok(graph)
+}
+
+/**
+ * Read a maximum flow problem (`p max`) from *reader*.
+ * The file must designate exactly one source (`n s`) and one sink (`n t`).
+ */
+pub fn read_max_flow<R, G, N>(reader: R) -> Result<MaxFlow<G>, ReadError>
+where
+    R: io::BufRead,
+    G: Graph<N>,
+    N: num_traits::Num + FromStr,
+{
+    let (graph, terminals, last_line) = read_problem(reader, "max")?;
+    let mut source = None;
+    let mut sink = None;
+    for (line, (node, kind)) in terminals {
+        let slot = match kind {
+            's' => &mut source,
+            _ => &mut sink,
+        };
+        if slot.replace(node).is_some() {
+            return Err(ReadError::syntax(line, "duplicate node designator"));
+        }
+    }
+    match (source, sink) {
+        (Some(source), Some(sink)) => Ok(MaxFlow {
+            graph,
+            source,
+            sink,
+        }),
+        (None, _) => Err(ReadError::syntax(last_line, "missing source designator")),
+        (_, None) => Err(ReadError::syntax(last_line, "missing sink designator")),
+    }
+}
+
+/**
+ * Write the given [GraphVisitor](visitor::GraphVisitor) into *writer*
+ * as a shortest path problem (`p sp`). Arcs of undirect graphs
+ * are written in both directions.
+ */
+pub fn write_shortest_path<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N>,
+    N: num_traits::Num + Copy + fmt::Display,
+{
+    writeln!(writer, "p sp {} {}", g.node_count(), g.arc_count())?;
+    write_arcs(writer, g)
+}
+
+/**
+ * Write the given [GraphVisitor](visitor::GraphVisitor) into *writer*
+ * as a maximum flow problem (`p max`) from *source* to *sink*.
+ * Arcs of undirect graphs are written in both directions.
+ */
+pub fn write_max_flow<W, G, N>(writer: &mut W, g: G, source: usize, sink: usize) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N>,
+    N: num_traits::Num + Copy + fmt::Display,
+{
+    writeln!(writer, "p max {} {}", g.node_count(), g.arc_count())?;
+    writeln!(writer, "n {} s", source + 1)?;
+    writeln!(writer, "n {} t", sink + 1)?;
+    write_arcs(writer, g)
+}
+
+fn write_arcs<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N>,
+    N: num_traits::Num + Copy + fmt::Display,
+{
+    let mut status = Ok(());
+    g.arc_visitor(|i, j, w| {
+        if status.is_ok() {
+            status = writeln!(writer, "a {} {} {}", i + 1, j + 1, w);
+        }
+    });
+    status
+}
+
+type Terminals = Vec<(usize, (usize, char))>;
+
+fn read_problem<R, G, N>(reader: R, kind: &str) -> Result<(G, Terminals, usize), ReadError>
+where
+    R: io::BufRead,
+    G: Graph<N>,
+    N: num_traits::Num + FromStr,
+{
+    let mut graph: Option<(G, usize, usize)> = None;
+    let mut found_arcs = 0;
+    let mut terminals = vec![];
+    let mut last_line = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_num = index + 1;
+        last_line = line_num;
+        let mut tokens = line.split_whitespace();
+        match (tokens.next(), &mut graph) {
+            (None, _) | (Some("c"), _) => {}
+            (Some("p"), None) => {
+                let found_kind = next_token(&mut tokens, line_num, "problem type")?;
+                if found_kind != kind {
+                    let message =
+                        format!("expected problem type \"{kind}\", found \"{found_kind}\"");
+                    return Err(ReadError::syntax(line_num, message));
+                }
+                let nodes = parse_token(&mut tokens, line_num, "node count")?;
+                let arcs = parse_token(&mut tokens, line_num, "arc count")?;
+                check_end(&mut tokens, line_num)?;
+                graph = Some((G::new(nodes, GraphType::Direct), nodes, arcs));
+            }
+            (Some("p"), Some(_)) => {
+                return Err(ReadError::syntax(line_num, "duplicate problem line"));
+            }
+            (Some("a"), Some((g, nodes, _))) => {
+                let src = parse_node(&mut tokens, line_num, *nodes)?;
+                let dst = parse_node(&mut tokens, line_num, *nodes)?;
+                let weight = parse_token(&mut tokens, line_num, "arc weight")?;
+                check_end(&mut tokens, line_num)?;
+                g.add_new_arc(src, dst, weight);
+                found_arcs += 1;
+            }
+            (Some("n"), Some((_, nodes, _))) => {
+                let node = parse_node(&mut tokens, line_num, *nodes)?;
+                let designator = match next_token(&mut tokens, line_num, "node designator")? {
+                    "s" => 's',
+                    "t" => 't',
+                    other => {
+                        let message = format!("unknown node designator \"{other}\"");
+                        return Err(ReadError::syntax(line_num, message));
+                    }
+                };
+                check_end(&mut tokens, line_num)?;
+                terminals.push((line_num, (node, designator)));
+            }
+            (Some("a" | "n"), None) => {
+                return Err(ReadError::syntax(line_num, "missing problem line"));
+            }
+            (Some(other), _) => {
+                let message = format!("unknown line descriptor \"{other}\"");
+                return Err(ReadError::syntax(line_num, message));
+            }
+        }
+    }
+
+    let (graph, _, arcs) =
+        graph.ok_or_else(|| ReadError::syntax(last_line, "missing problem line"))?;
+    if arcs != found_arcs {
+        let message = format!("expected {arcs} arcs, found {found_arcs}");
+        return Err(ReadError::syntax(last_line, message));
+    }
+    Ok((graph, terminals, last_line))
+}
+
+fn next_token<'a, I>(tokens: &mut I, line: usize, what: &str) -> Result<&'a str, ReadError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    tokens
+        .next()
+        .ok_or_else(|| ReadError::syntax(line, format!("missing {what}")))
+}
+
+fn parse_token<'a, I, T>(tokens: &mut I, line: usize, what: &str) -> Result<T, ReadError>
+where
+    I: Iterator<Item = &'a str>,
+    T: FromStr,
+{
+    let token = next_token(tokens, line, what)?;
+    token
+        .parse()
+        .map_err(|_| ReadError::syntax(line, format!("invalid {what} \"{token}\"")))
+}
+
+fn parse_node<'a, I>(tokens: &mut I, line: usize, nodes: usize) -> Result<usize, ReadError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let node: usize = parse_token(tokens, line, "node")?;
+    if node == 0 || node > nodes {
+        let message = format!("node {node} out of range 1..={nodes}");
+        Err(ReadError::syntax(line, message))
+    } else {
+        Ok(node - 1)
+    }
+}
+
+fn check_end<'a, I>(tokens: &mut I, line: usize) -> Result<(), ReadError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match tokens.next() {
+        Some(token) => Err(ReadError::syntax(
+            line,
+            format!("unexpected token \"{token}\""),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::{AdjList, MatrixGraph};
+
+    const MAX_FLOW: &str = "c max flow instance
+p max 4 5
+n 1 s
+n 4 t
+c arcs
+a 1 2 4
+a 1 3 2
+a 2 3 1
+a 2 4 3
+a 3 4 5
+";
+
+    #[test]
+    fn test_read_max_flow() {
+        let problem: MaxFlow<MatrixGraph<u32>> = read_max_flow(MAX_FLOW.as_bytes()).unwrap();
+        assert_eq!(problem.source, 0);
+        assert_eq!(problem.sink, 3);
+        let arcs: Vec<_> = problem.graph.arc_iterator().collect();
+        let expect = vec![(0, 1, 4), (0, 2, 2), (1, 2, 1), (1, 3, 3), (2, 3, 5)];
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_max_flow_round_trip() {
+        let problem: MaxFlow<AdjList<u32>> = read_max_flow(MAX_FLOW.as_bytes()).unwrap();
+        let mut buff = vec![];
+        write_max_flow(&mut buff, &problem.graph, problem.source, problem.sink).unwrap();
+        let expect = "p max 4 5\nn 1 s\nn 4 t\na 1 2 4\na 1 3 2\na 2 3 1\na 2 4 3\na 3 4 5\n";
+        assert_eq!(String::from_utf8(buff).unwrap(), expect);
+    }
+
+    #[test]
+    fn test_shortest_path_round_trip() {
+        let mut graph = AdjList::new_direct(3);
+        graph.add_new_arc(0, 1, 1.5);
+        graph.add_new_arc(2, 0, 2.0);
+
+        let mut buff = vec![];
+        write_shortest_path(&mut buff, &graph).unwrap();
+        assert_eq!(
+            String::from_utf8(buff.clone()).unwrap(),
+            "p sp 3 2\na 1 2 1.5\na 3 1 2\n"
+        );
+        let new_graph: AdjList<f64> = read_shortest_path(buff.as_slice()).unwrap();
+        assert_eq!(graph, new_graph);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let err = read_shortest_path::<_, AdjList<u32>, u32>(MAX_FLOW.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: expected problem type \"sp\", found \"max\""
+        );
+
+        let source = "p sp 2 1\na 1 3 4\n";
+        let err = read_shortest_path::<_, AdjList<u32>, u32>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: node 3 out of range 1..=2");
+
+        let source = "p sp 2 2\na 1 2 4\n";
+        let err = read_shortest_path::<_, AdjList<u32>, u32>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected 2 arcs, found 1");
+
+        let source = "a 1 2 4\np sp 2 1\n";
+        let err = read_shortest_path::<_, AdjList<u32>, u32>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: missing problem line");
+
+        let source = "p max 2 1\nn 1 s\na 1 2 4\n";
+        let err = read_max_flow::<_, AdjList<u32>, u32>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: missing sink designator");
+
+        let source = "p max 2 1\nn 1 s\na 1 2 x\n";
+        let err = read_max_flow::<_, AdjList<u32>, u32>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: invalid arc weight \"x\"");
+    }
+}
@@ -5,7 +5,9 @@
 
 use super::visitor;
 use super::{GetGraphType, GraphType};
+use std::collections::HashSet;
 use std::fmt;
+use std::io;
 
 /**
  * Convert the given [GraphVisitor](visitor::GraphVisitor)
@@ -16,60 +18,250 @@ where
     G: visitor::GraphVisitor<N> + GetGraphType + Copy,
     N: num_traits::Num + Default + Clone + Copy + std::fmt::Display,
 {
-    let body = build_body(g);
+    to_highlighted_dot_source(g, &Highlight::default())
+}
+
+/**
+ * Write the [dot](https://graphviz.org/) source code of the given
+ * [GraphVisitor](visitor::GraphVisitor) into *writer*. Each statement
+ * is written as soon as it is visited, so the whole source code
+ * is never kept in memory. Wrap *writer* into a [std::io::BufWriter]
+ * when writing into a file.
+ */
+pub fn write_dot<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Default + Clone + Copy + std::fmt::Display,
+{
+    write_highlighted_dot(writer, g, &Highlight::default())
+}
+
+/**
+ * Convert the given [GraphVisitor](visitor::GraphVisitor)
+ * into [dot](https://graphviz.org/) source code, emphasizing
+ * the arcs and grouping the nodes described by *highlight*.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::dot::{to_highlighted_dot_source, Highlight};
+ *
+ * let mut graph = AdjList::<u32>::new_direct(3);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 2, 2);
+ * graph.add_new_arc(2, 0, 3);
+ *
+ * let highlight = Highlight::from_path(&[0, 1, 2]);
+ * let dot_code = to_highlighted_dot_source(&graph, &highlight);
+ * assert!(dot_code.contains("n0 -> n1 [label=\"1\", color=\"red\", style=\"bold\"];"));
+ * assert!(dot_code.contains("n2 -> n0 [label=\"3\"];"));
+ * ```
+ */
+pub fn to_highlighted_dot_source<G, N>(g: G, highlight: &Highlight) -> String
+where
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Default + Clone + Copy + std::fmt::Display,
+{
+    let mut buff = Vec::with_capacity(g.total_entries() * 32);
+    write_highlighted_dot(&mut buff, g, highlight).expect("writing into a Vec cannot fail");
+    String::from_utf8(buff).expect("dot source code is always valid UTF-8")
+}
+
+/**
+ * Like [write_dot], emphasizing the arcs and grouping
+ * the nodes described by *highlight*.
+ */
+pub fn write_highlighted_dot<W, G, N>(writer: &mut W, g: G, highlight: &Highlight) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Default + Clone + Copy + std::fmt::Display,
+{
     let gtype = get_graph_type(g);
-    format!("{gtype} {{\n{body}\n}}")
+    write!(writer, "{gtype} {{")?;
+    write_body(writer, g, highlight)?;
+    write!(writer, "\n}}")
+}
+
+/**
+ * Describe which parts of a graph should stand out
+ * in the generated dot source code: a set of arcs, rendered
+ * with the given attributes (bold and red by default), and
+ * a list of node sets, each one rendered as a
+ * `subgraph cluster_*` block.
+ * In undirect graphs the arc (i, j) is equivalent to the
+ * arc (j, i), so highlighting one of them highlights both.
+ */
+#[derive(Clone, Debug)]
+pub struct Highlight {
+    arcs: HashSet<(usize, usize)>,
+    clusters: Vec<Vec<usize>>,
+    arc_attributes: String,
+}
+
+impl Default for Highlight {
+    fn default() -> Self {
+        Self {
+            arcs: HashSet::new(),
+            clusters: vec![],
+            arc_attributes: "color=\"red\", style=\"bold\"".to_owned(),
+        }
+    }
 }
 
-struct BuildBody {
+impl Highlight {
+    /**
+     * Highlight each arc connecting two consecutive
+     * nodes in *path*. To highlight a closed tour repeat
+     * the first node at the end of the path.
+     */
+    pub fn from_path(path: &[usize]) -> Self {
+        let mut output = Self::default();
+        output.add_path(path);
+        output
+    }
+
+    /**
+     * Highlight the given arcs.
+     */
+    pub fn from_arcs<I>(arcs: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut output = Self::default();
+        arcs.into_iter().for_each(|(i, j)| output.add_arc(i, j));
+        output
+    }
+
+    /**
+     * Highlight the arc from ```src``` to ```dst```.
+     */
+    pub fn add_arc(&mut self, src: usize, dst: usize) {
+        self.arcs.insert((src, dst));
+    }
+
+    /**
+     * Highlight each arc connecting two consecutive
+     * nodes in *path*.
+     */
+    pub fn add_path(&mut self, path: &[usize]) {
+        path.windows(2).for_each(|w| self.add_arc(w[0], w[1]));
+    }
+
+    /**
+     * Group the given nodes into a new `subgraph cluster_*` block.
+     * Clusters are numbered in insertion order.
+     */
+    pub fn add_cluster<I>(&mut self, nodes: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.clusters.push(nodes.into_iter().collect());
+    }
+
+    /**
+     * Replace the dot attributes used to render highlighted
+     * arcs, for example ```color="blue", penwidth=3```.
+     */
+    pub fn set_arc_attributes(&mut self, attributes: &str) {
+        self.arc_attributes = attributes.to_owned();
+    }
+
+    fn is_highlighted(&self, gtype: GraphType, i: usize, j: usize) -> bool {
+        match gtype {
+            GraphType::Direct => self.arcs.contains(&(i, j)),
+            GraphType::Undirect => self.arcs.contains(&(i, j)) || self.arcs.contains(&(j, i)),
+        }
+    }
+}
+
+struct WriteBody<'a, W: io::Write> {
     add_arc_check: &'static dyn Fn(usize, usize) -> bool,
-    str_buff: Vec<String>,
+    writer: &'a mut W,
+    status: io::Result<()>,
+    empty: bool,
     arrow: &'static str,
+    gtype: GraphType,
+    highlight: &'a Highlight,
 }
 
-impl BuildBody {
+impl<'a, W: io::Write> WriteBody<'a, W> {
     fn new(
-        size: usize,
+        writer: &'a mut W,
         arrow: &'static str,
         add_arc_check: &'static dyn Fn(usize, usize) -> bool,
+        gtype: GraphType,
+        highlight: &'a Highlight,
     ) -> Self {
-        let str_buff = Vec::with_capacity(size);
         Self {
-            str_buff,
+            writer,
+            status: Ok(()),
+            empty: true,
             arrow,
             add_arc_check,
+            gtype,
+            highlight,
+        }
+    }
+
+    fn write_stmt(&mut self, args: fmt::Arguments) {
+        if self.status.is_ok() {
+            self.empty = false;
+            self.status = self.writer.write_fmt(format_args!("\n{args}"));
         }
     }
 
     fn add_node<N: fmt::Display>(&mut self, i: usize, n: N) {
-        let node_stmt = format!("\tn{i} [label=\"{n}\"];");
-        self.str_buff.push(node_stmt);
+        self.write_stmt(format_args!("\tn{i} [label=\"{n}\"];"));
     }
 
     fn add_arc<N: fmt::Display>(&mut self, i: usize, j: usize, n: N) {
         if (self.add_arc_check)(i, j) {
-            let node_stmt = format!("\tn{} {} n{} [label=\"{}\"];", i, self.arrow, j, n);
-            self.str_buff.push(node_stmt);
+            let arrow = self.arrow;
+            if self.highlight.is_highlighted(self.gtype, i, j) {
+                let attributes = &self.highlight.arc_attributes;
+                self.write_stmt(format_args!(
+                    "\tn{i} {arrow} n{j} [label=\"{n}\", {attributes}];"
+                ));
+            } else {
+                self.write_stmt(format_args!("\tn{i} {arrow} n{j} [label=\"{n}\"];"));
+            }
+        }
+    }
+
+    fn add_clusters(&mut self) {
+        let highlight = self.highlight;
+        for (k, cluster) in highlight.clusters.iter().enumerate() {
+            self.write_stmt(format_args!("\tsubgraph cluster_{k} {{"));
+            for i in cluster {
+                self.write_stmt(format_args!("\t\tn{i};"));
+            }
+            self.write_stmt(format_args!("\t}}"));
         }
     }
 
-    fn build_str(self) -> String {
-        self.str_buff.join("\n")
+    fn finish(self) -> io::Result<()> {
+        // an empty body still takes its own line
+        match self.status {
+            Ok(()) if self.empty => self.writer.write_all(b"\n"),
+            status => status,
+        }
     }
 }
 
-fn build_body<G, N>(g: G) -> String
+fn write_body<W, G, N>(writer: &mut W, g: G, highlight: &Highlight) -> io::Result<()>
 where
+    W: io::Write,
     G: visitor::GraphVisitor<N> + GetGraphType + Copy,
     N: num_traits::Num + Default + Clone + Copy + std::fmt::Display,
 {
     let arrow = get_arrow(g);
     let f = get_arc_insert_logic(g);
-    let count = g.total_entries();
-    let mut str_builder = BuildBody::new(count, arrow, f);
-    g.node_visitor(|i, n| str_builder.add_node(i, n));
-    g.arc_visitor(|i, j, n| str_builder.add_arc(i, j, n));
-    str_builder.build_str()
+    let mut body_writer = WriteBody::new(writer, arrow, f, g.graph_type(), highlight);
+    g.node_visitor(|i, n| body_writer.add_node(i, n));
+    g.arc_visitor(|i, j, n| body_writer.add_arc(i, j, n));
+    body_writer.add_clusters();
+    body_writer.finish()
 }
 
 fn get_arc_insert_logic<G: GetGraphType>(g: G) -> &'static dyn Fn(usize, usize) -> bool {
@@ -111,6 +303,16 @@ mod test {
         assert_eq!(dot_code, expect)
     }
 
+    #[test]
+    fn test_dot_build_empty_graph() {
+        let graph = adjacency_list_graph::AdjList::<u32>::new_direct(0);
+        assert_eq!(to_dot_source(&graph), "digraph {\n\n}");
+        let graph = adjacency_list_graph::AdjList::<u32>::new_undirect(0);
+        let mut buff = vec![];
+        write_dot(&mut buff, &graph).unwrap();
+        assert_eq!(buff, b"graph {\n\n}");
+    }
+
     #[test]
     fn test_dot_build_undirect_graph() {
         let mut graph = adjacency_list_graph::AdjList::new_undirect(4);
@@ -122,4 +324,70 @@ mod test {
         let expect = "graph {\n\tn0 [label=\"0\"];\n\tn1 [label=\"0\"];\n\tn2 [label=\"0\"];\n\tn3 [label=\"0\"];\n\tn0 -- n1 [label=\"1.5\"];\n\tn1 -- n2 [label=\"2.5\"];\n\tn2 -- n3 [label=\"11.5\"];\n}";
         assert_eq!(dot_code, expect)
     }
+
+    #[test]
+    fn test_dot_highlight_path() {
+        let mut graph = adjacency_list_graph::AdjList::new_direct(3);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 0, 3);
+        graph.add_new_arc(1, 0, 4);
+
+        let highlight = Highlight::from_path(&[0, 1, 2]);
+        let dot_code = to_highlighted_dot_source(&graph, &highlight);
+        let expect = "digraph {\n\tn0 [label=\"0\"];\n\tn1 [label=\"0\"];\n\tn2 [label=\"0\"];\n\tn0 -> n1 [label=\"1\", color=\"red\", style=\"bold\"];\n\tn1 -> n2 [label=\"2\", color=\"red\", style=\"bold\"];\n\tn1 -> n0 [label=\"4\"];\n\tn2 -> n0 [label=\"3\"];\n}";
+        assert_eq!(dot_code, expect)
+    }
+
+    #[test]
+    fn test_dot_highlight_undirect_clusters() {
+        let mut graph = adjacency_list_graph::AdjList::new_undirect(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(3, 2, 3);
+
+        let mut highlight = Highlight::from_arcs([(2, 1)]);
+        highlight.set_arc_attributes("color=\"blue\"");
+        highlight.add_cluster([0, 1]);
+        highlight.add_cluster([2, 3]);
+        let dot_code = to_highlighted_dot_source(&graph, &highlight);
+        let expect = "graph {\n\tn0 [label=\"0\"];\n\tn1 [label=\"0\"];\n\tn2 [label=\"0\"];\n\tn3 [label=\"0\"];\n\tn0 -- n1 [label=\"1\"];\n\tn1 -- n2 [label=\"2\", color=\"blue\"];\n\tn2 -- n3 [label=\"3\"];\n\tsubgraph cluster_0 {\n\t\tn0;\n\t\tn1;\n\t}\n\tsubgraph cluster_1 {\n\t\tn2;\n\t\tn3;\n\t}\n}";
+        assert_eq!(dot_code, expect)
+    }
+
+    #[test]
+    fn test_write_dot() {
+        let mut graph = adjacency_list_graph::AdjList::new_direct(3);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+
+        let mut buff = vec![];
+        write_dot(&mut buff, &graph).unwrap();
+        assert_eq!(String::from_utf8(buff).unwrap(), to_dot_source(&graph));
+    }
+
+    #[test]
+    fn test_write_dot_error() {
+        struct FailAfter(usize);
+        impl io::Write for FailAfter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(io::Error::other("full"));
+                }
+                self.0 -= 1;
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut graph = adjacency_list_graph::AdjList::new_undirect(3);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+
+        let mut writer = FailAfter(3);
+        let err = write_dot(&mut writer, &graph).unwrap_err();
+        assert_eq!(err.to_string(), "full");
+    }
 }
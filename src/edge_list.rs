@@ -0,0 +1,436 @@
+/*!
+ * Read and write graphs as plain edge lists: one arc per line
+ * made of the source node, the destination node and, optionally,
+ * the arc weight. Both whitespace separated lists and
+ * CSV files (`src,dst,weight`) are supported.
+ */
+
+use super::read_error::ReadError;
+use super::{Graph, GraphType};
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/**
+ * Describe the layout of an edge list.
+ */
+#[derive(Clone, Debug)]
+pub struct EdgeListOptions {
+    /**
+     * Column separator, when ```None``` columns are separated by any whitespace.
+     */
+    pub delimiter: Option<char>,
+    /**
+     * Whether the first non comment line is a header row.
+     */
+    pub header: bool,
+    /**
+     * Lines starting with this prefix are ignored.
+     */
+    pub comment: Option<String>,
+    /**
+     * Whether node ids start from 1 instead of 0.
+     */
+    pub one_based: bool,
+    /**
+     * Type of the graph built by [read_edge_list]. When writing an
+     * undirect graph each arc is written once.
+     */
+    pub gtype: GraphType,
+    /**
+     * Number of nodes in the graph. When ```None``` it is set to
+     * the largest node id found plus one, at the cost of keeping
+     * all arcs in memory until the whole list is read, and ids
+     * must stay below [MAX_INFERRED_NODE_COUNT]. When set, arcs
+     * are inserted into the graph as they are read.
+     */
+    pub node_count: Option<usize>,
+}
+
+impl Default for EdgeListOptions {
+    /**
+     * Whitespace separated, 0-based, direct edge list
+     * with `#` comments and no header.
+     */
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            header: false,
+            comment: Some("#".to_owned()),
+            one_based: false,
+            gtype: GraphType::Direct,
+            node_count: None,
+        }
+    }
+}
+
+impl EdgeListOptions {
+    /**
+     * Comma separated, 0-based, direct edge list
+     * with a header row and no comments.
+     */
+    pub fn csv() -> Self {
+        Self {
+            delimiter: Some(','),
+            header: true,
+            comment: None,
+            ..Self::default()
+        }
+    }
+}
+
+/**
+ * Largest node count [read_edge_list] infers when
+ * [EdgeListOptions::node_count] is ```None```: larger ids are
+ * rejected instead of allocating a graph with as many nodes.
+ */
+pub const MAX_INFERRED_NODE_COUNT: usize = 1 << 24;
+
+/**
+ * A single line of an edge list: source, destination
+ * and, if the line has a weight column, the arc weight.
+ */
+pub type EdgeRecord<N> = (usize, usize, Option<N>);
+
+/**
+ * Iterate over the records of an edge list, one line at a time.
+ * Node ids are always converted to 0-based indexes.
+ *
+ * ```
+ * use simplegraph::edge_list::{EdgeListOptions, EdgeListReader};
+ *
+ * let source = "src,dst,weight\n1,2,0.5\n2,3\n";
+ * let mut options = EdgeListOptions::csv();
+ * options.one_based = true;
+ * let records: Vec<_> = EdgeListReader::<_, f64>::new(source.as_bytes(), &options)
+ *     .collect::<Result<_, _>>()
+ *     .unwrap();
+ * assert_eq!(records, vec![(0, 1, Some(0.5)), (1, 2, None)]);
+ * ```
+ */
+pub struct EdgeListReader<'a, R, N> {
+    lines: io::Lines<R>,
+    line_num: usize,
+    skip_header: bool,
+    options: &'a EdgeListOptions,
+    weight: PhantomData<N>,
+}
+
+impl<'a, R, N> EdgeListReader<'a, R, N>
+where
+    R: io::BufRead,
+    N: FromStr,
+{
+    pub fn new(reader: R, options: &'a EdgeListOptions) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_num: 0,
+            skip_header: options.header,
+            options,
+            weight: PhantomData,
+        }
+    }
+
+    /**
+     * Return the number of the last line read.
+     */
+    pub fn line(&self) -> usize {
+        self.line_num
+    }
+
+    fn parse_line(&self, line: &str) -> Result<EdgeRecord<N>, ReadError> {
+        let mut fields: Vec<&str> = match self.options.delimiter {
+            Some(delimiter) => line.split(delimiter).map(str::trim).collect(),
+            None => line.split_whitespace().collect(),
+        };
+        if fields.last() == Some(&"") {
+            fields.pop();
+        }
+        match fields.as_slice() {
+            [src, dst] => Ok((self.parse_node(src)?, self.parse_node(dst)?, None)),
+            [src, dst, weight] => {
+                let weight = weight.parse().map_err(|_| {
+                    ReadError::syntax(self.line_num, format!("invalid weight \"{weight}\""))
+                })?;
+                Ok((self.parse_node(src)?, self.parse_node(dst)?, Some(weight)))
+            }
+            _ => {
+                let message = format!("expected 2 or 3 columns, found {}", fields.len());
+                Err(ReadError::syntax(self.line_num, message))
+            }
+        }
+    }
+
+    fn parse_node(&self, token: &str) -> Result<usize, ReadError> {
+        let error = || ReadError::syntax(self.line_num, format!("invalid node id \"{token}\""));
+        let id: usize = token.parse().map_err(|_| error())?;
+        if self.options.one_based {
+            id.checked_sub(1).ok_or_else(error)
+        } else {
+            Ok(id)
+        }
+    }
+
+    fn is_comment(&self, line: &str) -> bool {
+        match &self.options.comment {
+            Some(prefix) => line.starts_with(prefix.as_str()),
+            None => false,
+        }
+    }
+}
+
+impl<'a, R, N> Iterator for EdgeListReader<'a, R, N>
+where
+    R: io::BufRead,
+    N: FromStr,
+{
+    type Item = Result<EdgeRecord<N>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+            self.line_num += 1;
+            let line = line.trim();
+            if line.is_empty() || self.is_comment(line) {
+                continue;
+            }
+            if self.skip_header {
+                self.skip_header = false;
+                continue;
+            }
+            return Some(self.parse_line(line));
+        }
+    }
+}
+
+/**
+ * Build a graph from the edge list read from *reader*. Arcs
+ * without a weight column are inserted using
+ * [add_new_default_arc](Graph::add_new_default_arc).
+ *
+ * ```
+ * use simplegraph::AdjList;
+ * use simplegraph::edge_list::{read_edge_list, EdgeListOptions};
+ *
+ * let source = "# a comment\n0 1 2.5\n1 2\n";
+ * let graph: AdjList<f64> = read_edge_list(source.as_bytes(), &EdgeListOptions::default()).unwrap();
+ * let arcs: Vec<_> = graph.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 2.5), (1, 2, 0.0)]);
+ * ```
+ */
+pub fn read_edge_list<R, G, N>(reader: R, options: &EdgeListOptions) -> Result<G, ReadError>
+where
+    R: io::BufRead,
+    G: Graph<N>,
+    N: FromStr,
+{
+    let mut records = EdgeListReader::new(reader, options);
+    // ids as they appear in the input, for error messages
+    let base = options.one_based as usize;
+    match options.node_count {
+        Some(node_count) => {
+            let mut graph = G::new(node_count, options.gtype);
+            while let Some(record) = records.next() {
+                let (i, j, w) = record?;
+                if i >= node_count || j >= node_count {
+                    let (i, j) = (i + base, j + base);
+                    let end = node_count + base;
+                    let message = format!("arc ({i}, {j}) out of range {base}..{end}");
+                    return Err(ReadError::syntax(records.line(), message));
+                }
+                insert_arc(&mut graph, i, j, w);
+            }
+            Ok(graph)
+        }
+        None => {
+            let mut arcs = vec![];
+            let mut node_count = 0;
+            while let Some(record) = records.next() {
+                let (i, j, w) = record?;
+                let last = i.max(j);
+                if last >= MAX_INFERRED_NODE_COUNT {
+                    let id = last + base;
+                    let message = format!(
+                        "node id {id} exceeds the limit of {MAX_INFERRED_NODE_COUNT} nodes, set node_count"
+                    );
+                    return Err(ReadError::syntax(records.line(), message));
+                }
+                node_count = node_count.max(last + 1);
+                arcs.push((i, j, w));
+            }
+            let mut graph = G::new(node_count, options.gtype);
+            for (i, j, w) in arcs {
+                insert_arc(&mut graph, i, j, w);
+            }
+            Ok(graph)
+        }
+    }
+}
+
+/**
+ * Write the given arcs, usually obtained from
+ * ```arc_iterator```, into *writer* as an edge list.
+ * When [EdgeListOptions::gtype] is [GraphType::Undirect]
+ * only the arcs (*i*, *j*) with *i* <= *j* are written.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::edge_list::{write_edge_list, EdgeListOptions};
+ *
+ * let mut graph = AdjList::new_direct(3);
+ * graph.add_new_arc(0, 1, 5);
+ * graph.add_new_arc(2, 0, 7);
+ *
+ * let mut buff = vec![];
+ * write_edge_list(&mut buff, graph.arc_iterator(), &EdgeListOptions::csv()).unwrap();
+ * assert_eq!(String::from_utf8(buff).unwrap(), "src,dst,weight\n0,1,5\n2,0,7\n");
+ * ```
+ */
+pub fn write_edge_list<W, I, N>(
+    writer: &mut W,
+    arcs: I,
+    options: &EdgeListOptions,
+) -> io::Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = (usize, usize, N)>,
+    N: fmt::Display,
+{
+    let delimiter = options.delimiter.unwrap_or(' ');
+    let offset = usize::from(options.one_based);
+    if options.header {
+        writeln!(writer, "src{delimiter}dst{delimiter}weight")?;
+    }
+    for (i, j, w) in arcs {
+        if options.gtype == GraphType::Direct || i <= j {
+            writeln!(
+                writer,
+                "{}{delimiter}{}{delimiter}{}",
+                i + offset,
+                j + offset,
+                w
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_arc<G, N>(graph: &mut G, i: usize, j: usize, w: Option<N>)
+where
+    G: Graph<N>,
+{
+    match w {
+        Some(w) => graph.add_new_arc(i, j, w),
+        None => graph.add_new_default_arc(i, j),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::{AdjList, MatrixGraph};
+
+    #[test]
+    fn test_read_csv() {
+        let source = "src,dst,weight\n1,2,1.5\n\n3,1,\n2,3,-2\n";
+        let mut options = EdgeListOptions::csv();
+        options.one_based = true;
+        options.gtype = GraphType::Undirect;
+        let graph: MatrixGraph<f64> = read_edge_list(source.as_bytes(), &options).unwrap();
+        let arcs: Vec<_> = graph.arc_iterator().collect();
+        let expect = vec![
+            (0, 1, 1.5),
+            (0, 2, 0.0),
+            (1, 0, 1.5),
+            (1, 2, -2.0),
+            (2, 0, 0.0),
+            (2, 1, -2.0),
+        ];
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = AdjList::new_undirect(4);
+        graph.add_new_arc(0, 1, 3);
+        graph.add_new_arc(3, 1, 4);
+        graph.add_new_arc(2, 3, 5);
+
+        let options = EdgeListOptions {
+            delimiter: Some(';'),
+            one_based: true,
+            gtype: GraphType::Undirect,
+            node_count: Some(4),
+            ..EdgeListOptions::default()
+        };
+        let mut buff = vec![];
+        write_edge_list(&mut buff, graph.arc_iterator(), &options).unwrap();
+        assert_eq!(
+            String::from_utf8(buff.clone()).unwrap(),
+            "1;2;3\n2;4;4\n3;4;5\n"
+        );
+
+        let new_graph: AdjList<i32> = read_edge_list(buff.as_slice(), &options).unwrap();
+        let mut arcs: Vec<_> = new_graph.arc_iterator().collect();
+        let mut expect: Vec<_> = graph.arc_iterator().collect();
+        arcs.sort_unstable();
+        expect.sort_unstable();
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let options = EdgeListOptions::default();
+        let source = "0 1\n# comment\n1 x\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: invalid node id \"x\"");
+
+        let source = "0 1 2 3\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: expected 2 or 3 columns, found 4");
+
+        let source = "0 1 2.5\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid weight \"2.5\"");
+
+        let source = format!("0 1\n{} 0\n", usize::MAX);
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        let message = format!(
+            "line 2: node id {} exceeds the limit of 16777216 nodes, set node_count",
+            usize::MAX
+        );
+        assert_eq!(err.to_string(), message);
+
+        let typo = "0 100000000000000 1\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(typo.as_bytes(), &options).unwrap_err();
+        let message =
+            "line 1: node id 100000000000000 exceeds the limit of 16777216 nodes, set node_count";
+        assert_eq!(err.to_string(), message);
+
+        let bounded = EdgeListOptions {
+            node_count: Some(2),
+            ..EdgeListOptions::default()
+        };
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &bounded).unwrap_err();
+        let message = format!("line 2: arc ({}, 0) out of range 0..2", usize::MAX);
+        assert_eq!(err.to_string(), message);
+
+        let options = EdgeListOptions {
+            one_based: true,
+            node_count: Some(2),
+            ..EdgeListOptions::default()
+        };
+        let source = "1 2\n2 3\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: arc (2, 3) out of range 1..3");
+
+        let source = "0 1\n";
+        let err = read_edge_list::<_, AdjList<i32>, i32>(source.as_bytes(), &options).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid node id \"0\"");
+    }
+}
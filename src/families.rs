@@ -0,0 +1,365 @@
+/*!
+ * Constructors for classic graph families. Arcs are inserted using
+ * [add_new_default_arc](Graph::add_new_default_arc): set their weights
+ * afterwards with [update_all_arcs_weight](Graph::update_all_arcs_weight).
+ * Grid and torus constructors also return the coordinates of each node,
+ * handy to compute geometric weights.
+ *
+ * In direct graphs, families with a natural orientation (path, cycle, star,
+ * wheel, complete bipartite and binary tree) follow it, while symmetric
+ * families (complete, grid, torus and hypercube) get arcs in both directions.
+ *
+ * ```
+ * use simplegraph::{Graph, GraphType, MatrixGraph};
+ * use simplegraph::families::grid_2d;
+ *
+ * let (mut graph, coords): (MatrixGraph<f64>, _) = grid_2d(3, 4, GraphType::Undirect);
+ * graph.update_all_arcs_weight(|i, j, _| {
+ *     let (x1, y1) = coords[i];
+ *     let (x2, y2) = coords[j];
+ *     (x1 - x2).hypot(y1 - y2)
+ * });
+ * assert_eq!(graph.arc_iterator().count(), 2 * 17);
+ * assert!(graph.arc_iterator().all(|(_, _, w)| w == 1.0));
+ * ```
+ */
+
+use super::{Graph, GraphType};
+
+/**
+ * Complete graph with *n* nodes: every node is connected to all the others.
+ */
+pub fn complete<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph = G::new(n, gtype);
+    for i in 0..n {
+        for j in i + 1..n {
+            add_symmetric(&mut graph, gtype, i, j);
+        }
+    }
+    graph
+}
+
+/**
+ * Path 0 -> 1 -> ... -> *n* - 1.
+ */
+pub fn path<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph = G::new(n, gtype);
+    for i in 1..n {
+        graph.add_new_default_arc(i - 1, i);
+    }
+    graph
+}
+
+/**
+ * Cycle 0 -> 1 -> ... -> *n* - 1 -> 0. With less than three
+ * nodes the cycle degenerates into a [path].
+ */
+pub fn cycle<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph: G = path(n, gtype);
+    if n > 2 {
+        graph.add_new_default_arc(n - 1, 0);
+    }
+    graph
+}
+
+/**
+ * Star with center 0 connected to the *n* - 1 leaves.
+ */
+pub fn star<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph = G::new(n, gtype);
+    for i in 1..n {
+        graph.add_new_default_arc(0, i);
+    }
+    graph
+}
+
+/**
+ * Wheel with hub 0 connected to the *n* - 1 nodes of
+ * the rim, the cycle 1 -> 2 -> ... -> *n* - 1 -> 1.
+ */
+pub fn wheel<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph: G = star(n, gtype);
+    for i in 2..n {
+        graph.add_new_default_arc(i - 1, i);
+    }
+    if n > 3 {
+        graph.add_new_default_arc(n - 1, 1);
+    }
+    graph
+}
+
+/**
+ * Grid with *rows* x *cols* nodes, node (*r*, *c*) has index
+ * *r* * *cols* + *c* and coordinates (*c*, *r*).
+ */
+pub fn grid_2d<G, N>(rows: usize, cols: usize, gtype: GraphType) -> (G, Vec<(f64, f64)>)
+where
+    G: Graph<N>,
+{
+    let (graph, coords) = lattice(&[rows, cols], false, gtype);
+    (graph, coords.into_iter().map(|p| (p[1], p[0])).collect())
+}
+
+/**
+ * Grid with *x* x *y* x *z* nodes, node (*i*, *j*, *k*) has index
+ * (*k* * *y* + *j*) * *x* + *i* and coordinates (*i*, *j*, *k*).
+ */
+pub fn grid_3d<G, N>(x: usize, y: usize, z: usize, gtype: GraphType) -> (G, Vec<(f64, f64, f64)>)
+where
+    G: Graph<N>,
+{
+    let (graph, coords) = lattice(&[z, y, x], false, gtype);
+    (
+        graph,
+        coords.into_iter().map(|p| (p[2], p[1], p[0])).collect(),
+    )
+}
+
+/**
+ * Like [grid_2d], with arcs connecting the first and
+ * the last node of each row and of each column.
+ */
+pub fn torus_2d<G, N>(rows: usize, cols: usize, gtype: GraphType) -> (G, Vec<(f64, f64)>)
+where
+    G: Graph<N>,
+{
+    let (graph, coords) = lattice(&[rows, cols], true, gtype);
+    (graph, coords.into_iter().map(|p| (p[1], p[0])).collect())
+}
+
+/**
+ * Like [grid_3d], with arcs connecting the first
+ * and the last node along each axis.
+ */
+pub fn torus_3d<G, N>(x: usize, y: usize, z: usize, gtype: GraphType) -> (G, Vec<(f64, f64, f64)>)
+where
+    G: Graph<N>,
+{
+    let (graph, coords) = lattice(&[z, y, x], true, gtype);
+    (
+        graph,
+        coords.into_iter().map(|p| (p[2], p[1], p[0])).collect(),
+    )
+}
+
+/**
+ * Hypercube of dimension *dim*: 2^*dim* nodes, two nodes are
+ * connected when their indexes differ in exactly one bit.
+ */
+pub fn hypercube<G, N>(dim: u32, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let n = 1 << dim;
+    let mut graph = G::new(n, gtype);
+    for i in 0..n {
+        for bit in 0..dim {
+            let j = i ^ (1 << bit);
+            if i < j {
+                add_symmetric(&mut graph, gtype, i, j);
+            }
+        }
+    }
+    graph
+}
+
+/**
+ * Complete bipartite graph: each of the first *a* nodes
+ * is connected to each of the following *b* nodes.
+ */
+pub fn complete_bipartite<G, N>(a: usize, b: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph = G::new(a + b, gtype);
+    for i in 0..a {
+        for j in a..a + b {
+            graph.add_new_default_arc(i, j);
+        }
+    }
+    graph
+}
+
+/**
+ * Complete binary tree with *n* nodes rooted in 0: node *i*
+ * is the parent of nodes 2*i* + 1 and 2*i* + 2.
+ */
+pub fn binary_tree<G, N>(n: usize, gtype: GraphType) -> G
+where
+    G: Graph<N>,
+{
+    let mut graph = G::new(n, gtype);
+    for i in 1..n {
+        graph.add_new_default_arc((i - 1) / 2, i);
+    }
+    graph
+}
+
+fn add_symmetric<G, N>(graph: &mut G, gtype: GraphType, i: usize, j: usize)
+where
+    G: Graph<N>,
+{
+    graph.add_new_default_arc(i, j);
+    if gtype == GraphType::Direct {
+        graph.add_new_default_arc(j, i);
+    }
+}
+
+/**
+ * Build a lattice with the given size along each axis, the last axis
+ * varies fastest. Return the graph and the coordinates of each node,
+ * in the same order as the axes.
+ */
+fn lattice<G, N>(sizes: &[usize], wrap: bool, gtype: GraphType) -> (G, Vec<Vec<f64>>)
+where
+    G: Graph<N>,
+{
+    let n = sizes.iter().product();
+    let mut graph = G::new(n, gtype);
+    let mut coords = Vec::with_capacity(n);
+    let mut point = vec![0; sizes.len()];
+    for i in 0..n {
+        let mut stride = 1;
+        for (axis, size) in sizes.iter().enumerate().rev() {
+            if point[axis] + 1 < *size {
+                add_symmetric(&mut graph, gtype, i, i + stride);
+            } else if wrap && *size > 2 {
+                add_symmetric(&mut graph, gtype, i, i + stride - size * stride);
+            }
+            stride *= size;
+        }
+        coords.push(point.iter().map(|c| *c as f64).collect());
+        next_point(&mut point, sizes);
+    }
+    (graph, coords)
+}
+
+fn next_point(point: &mut [usize], sizes: &[usize]) {
+    for (c, size) in point.iter_mut().zip(sizes).rev() {
+        *c += 1;
+        if *c < *size {
+            return;
+        }
+        *c = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::geometric::Metric;
+    use crate::tests;
+    use crate::visitor::GraphVisitor;
+    use crate::{AdjList, MatrixGraph};
+
+    fn degrees(graph: &AdjList<i32>) -> Vec<usize> {
+        let mut degrees = vec![0; graph.node_count()];
+        graph.arc_visitor(|i, _, _| degrees[i] += 1);
+        degrees
+    }
+
+    fn arcs(graph: &AdjList<i32>) -> Vec<(usize, usize)> {
+        graph.arc_iterator().map(|(i, j, _)| (i, j)).collect()
+    }
+
+    #[test]
+    fn test_complete() {
+        let graph: AdjList<i32> = complete(5, GraphType::Undirect);
+        assert!(degrees(&graph).iter().all(|d| *d == 4));
+        let graph: MatrixGraph<i32> = complete(5, GraphType::Direct);
+        assert_eq!(graph.arc_iterator().count(), 20);
+    }
+
+    #[test]
+    fn test_path_cycle() {
+        let graph: AdjList<i32> = path(4, GraphType::Direct);
+        assert_eq!(arcs(&graph), vec![(0, 1), (1, 2), (2, 3)]);
+        let graph: AdjList<i32> = cycle(4, GraphType::Direct);
+        assert_eq!(arcs(&graph), vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let graph: AdjList<i32> = cycle(2, GraphType::Undirect);
+        assert_eq!(arcs(&graph), vec![(0, 1), (1, 0)]);
+        let graph: AdjList<i32> = cycle(6, GraphType::Undirect);
+        assert!(degrees(&graph).iter().all(|d| *d == 2));
+    }
+
+    #[test]
+    fn test_star_wheel() {
+        let graph: AdjList<i32> = star(5, GraphType::Direct);
+        assert_eq!(degrees(&graph), vec![4, 0, 0, 0, 0]);
+        let graph: AdjList<i32> = wheel(6, GraphType::Undirect);
+        assert_eq!(degrees(&graph), vec![5, 3, 3, 3, 3, 3]);
+        let graph: MatrixGraph<i32> = wheel(4, GraphType::Direct);
+        assert_eq!(graph.arc_iterator().count(), 6);
+    }
+
+    #[test]
+    fn test_grid() {
+        let (graph, coords): (AdjList<i32>, _) = grid_2d(2, 3, GraphType::Undirect);
+        assert_eq!(degrees(&graph), vec![2, 3, 2, 2, 3, 2]);
+        assert_eq!(coords[4], (1.0, 1.0));
+        assert_eq!(coords[2], (2.0, 0.0));
+
+        let (mut graph, coords): (MatrixGraph<f64>, _) = grid_2d(3, 3, GraphType::Direct);
+        assert_eq!(graph.arc_iterator().count(), 24);
+        graph.update_all_arcs_weight(|i, j, _| Metric::Euclidean.distance(&coords[i], &coords[j]));
+        graph
+            .arc_iterator()
+            .for_each(|(_, _, w)| tests::approx_equal(w, 1.0, 1e-12));
+
+        let (graph, coords): (AdjList<i32>, _) = grid_3d(2, 3, 4, GraphType::Undirect);
+        assert_eq!((&graph).node_count(), 24);
+        assert_eq!((&graph).arc_count(), 2 * (12 + 16 + 18));
+        assert_eq!(coords[1], (1.0, 0.0, 0.0));
+        assert_eq!(coords[2], (0.0, 1.0, 0.0));
+        assert_eq!(coords[6], (0.0, 0.0, 1.0));
+        (&graph).arc_visitor(|i, j, _| {
+            let (x1, y1, z1) = coords[i];
+            let (x2, y2, z2) = coords[j];
+            let dist = (x1 - x2).abs() + (y1 - y2).abs() + (z1 - z2).abs();
+            assert_eq!(dist, 1.0);
+        });
+    }
+
+    #[test]
+    fn test_torus() {
+        let (graph, _): (AdjList<i32>, _) = torus_2d(3, 4, GraphType::Undirect);
+        assert!(degrees(&graph).iter().all(|d| *d == 4));
+        let (graph, _): (AdjList<i32>, _) = torus_2d(2, 4, GraphType::Undirect);
+        assert!(degrees(&graph).iter().all(|d| *d == 3));
+        let (graph, coords): (AdjList<i32>, _) = torus_3d(3, 3, 3, GraphType::Direct);
+        assert!(degrees(&graph).iter().all(|d| *d == 6));
+        assert_eq!(coords.len(), 27);
+    }
+
+    #[test]
+    fn test_hypercube() {
+        let graph: AdjList<i32> = hypercube(4, GraphType::Undirect);
+        assert_eq!((&graph).node_count(), 16);
+        assert!(degrees(&graph).iter().all(|d| *d == 4));
+        (&graph).arc_visitor(|i, j, _| assert_eq!((i ^ j).count_ones(), 1));
+    }
+
+    #[test]
+    fn test_bipartite_tree() {
+        let graph: AdjList<i32> = complete_bipartite(2, 3, GraphType::Undirect);
+        assert_eq!(degrees(&graph), vec![3, 3, 2, 2, 2]);
+        let graph: AdjList<i32> = binary_tree(6, GraphType::Direct);
+        assert_eq!(arcs(&graph), vec![(0, 1), (0, 2), (1, 3), (1, 4), (2, 5)]);
+    }
+}
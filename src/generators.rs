@@ -0,0 +1,382 @@
+/*!
+ * Random graph generators. Every generator takes the random number
+ * generator as argument: using a seeded generator, like
+ * [StdRng::seed_from_u64](rand::SeedableRng::seed_from_u64), makes
+ * the result reproducible. Arc weights are sampled with the given
+ * closure, called once for each arc with the generator, the source
+ * and the destination node. In undirect graphs the closure is called
+ * once for each pair of connected nodes.
+ * Generated graphs never contain self loops or parallel arcs.
+ *
+ * ```
+ * use rand::rngs::StdRng;
+ * use rand::{Rng, SeedableRng};
+ * use simplegraph::{AdjList, GraphType, GraphVisitor};
+ * use simplegraph::generators::gnm;
+ *
+ * let mut rng = StdRng::seed_from_u64(42);
+ * let graph: AdjList<f64> = gnm(&mut rng, 10, 15, GraphType::Undirect, |rng, _, _| rng.gen());
+ * assert_eq!((&graph).arc_count(), 30);
+ * ```
+ */
+
+use super::{Graph, GraphType};
+use rand::seq::{index, SliceRandom};
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+/**
+ * Erdős–Rényi G(*n*, *p*) model: build a graph with *n* nodes where
+ * each arc exists, independently from the others, with probability *p*.
+ */
+pub fn gnp<G, N, R, F>(rng: &mut R, n: usize, p: f64, gtype: GraphType, weight: F) -> G
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    let p = p.clamp(0.0, 1.0);
+    let mut arcs = vec![];
+    for i in 0..n {
+        let first = match gtype {
+            GraphType::Direct => 0,
+            GraphType::Undirect => i + 1,
+        };
+        for j in first..n {
+            if i != j && rng.gen_bool(p) {
+                arcs.push((i, j));
+            }
+        }
+    }
+    build(rng, n, gtype, arcs, weight)
+}
+
+/**
+ * Erdős–Rényi G(*n*, *m*) model: build a graph with *n* nodes and
+ * *m* arcs chosen uniformly at random among all the possible ones.
+ * In undirect graphs *m* counts the pairs of connected nodes.
+ *
+ * # Panics
+ * If *m* is larger than the number of possible arcs.
+ */
+pub fn gnm<G, N, R, F>(rng: &mut R, n: usize, m: usize, gtype: GraphType, weight: F) -> G
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    let possible = match gtype {
+        GraphType::Direct => n * n.saturating_sub(1),
+        GraphType::Undirect => n * n.saturating_sub(1) / 2,
+    };
+    assert!(
+        m <= possible,
+        "{m} arcs requested but a graph with {n} nodes has at most {possible}"
+    );
+    let mut indexes = index::sample(rng, possible, m).into_vec();
+    indexes.sort_unstable();
+    let arcs = indexes
+        .into_iter()
+        .map(|k| match gtype {
+            GraphType::Direct => direct_pair(k, n),
+            GraphType::Undirect => undirect_pair(k),
+        })
+        .collect();
+    build(rng, n, gtype, arcs, weight)
+}
+
+/**
+ * Barabási–Albert preferential attachment model: build an undirect graph
+ * with *n* nodes. Starting from *m* isolated nodes, each new node is connected
+ * to *m* distinct existing nodes chosen with probability proportional
+ * to their degree.
+ *
+ * # Panics
+ * If *m* is zero or not smaller than *n*.
+ */
+pub fn barabasi_albert<G, N, R, F>(rng: &mut R, n: usize, m: usize, weight: F) -> G
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    assert!(
+        m >= 1 && m < n,
+        "expected 1 <= m < n, found m = {m}, n = {n}"
+    );
+    let mut arcs = vec![];
+    // each node appears once for every arc it belongs to
+    let mut repeated = vec![];
+    let mut targets: Vec<usize> = (0..m).collect();
+    for source in m..n {
+        for &target in &targets {
+            arcs.push((target, source));
+        }
+        repeated.extend_from_slice(&targets);
+        repeated.extend(std::iter::repeat_n(source, m));
+
+        let mut chosen = HashSet::with_capacity(m);
+        targets.clear();
+        while targets.len() < m {
+            let node = *repeated.choose(rng).unwrap();
+            if chosen.insert(node) {
+                targets.push(node);
+            }
+        }
+    }
+    build(rng, n, GraphType::Undirect, arcs, weight)
+}
+
+/**
+ * Watts–Strogatz small-world model: build an undirect ring lattice with
+ * *n* nodes, each connected to its *k* / 2 nearest neighbours on each side,
+ * then rewire the far end of each arc to a random node with probability *beta*.
+ *
+ * # Panics
+ * If *k* is not smaller than *n*.
+ */
+pub fn watts_strogatz<G, N, R, F>(rng: &mut R, n: usize, k: usize, beta: f64, weight: F) -> G
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    assert!(k < n, "expected k < n, found k = {k}, n = {n}");
+    let beta = beta.clamp(0.0, 1.0);
+    let mut neighbours: Vec<HashSet<usize>> = (0..n).map(|_| HashSet::new()).collect();
+    let mut arcs = vec![];
+    for i in 0..n {
+        for d in 1..=k / 2 {
+            let j = (i + d) % n;
+            neighbours[i].insert(j);
+            neighbours[j].insert(i);
+            arcs.push((i, j));
+        }
+    }
+
+    for arc in arcs.iter_mut() {
+        let (i, j) = *arc;
+        // a node connected to all the others can not be rewired
+        if neighbours[i].len() + 1 < n && rng.gen_bool(beta) {
+            let mut new_j = rng.gen_range(0..n);
+            while new_j == i || neighbours[i].contains(&new_j) {
+                new_j = rng.gen_range(0..n);
+            }
+            neighbours[i].remove(&j);
+            neighbours[j].remove(&i);
+            neighbours[i].insert(new_j);
+            neighbours[new_j].insert(i);
+            *arc = (i, new_j);
+        }
+    }
+    build(rng, n, GraphType::Undirect, arcs, weight)
+}
+
+/**
+ * Build an undirect random *d*-regular graph with *n* nodes using
+ * the pairing model, restarting whenever the pairing gets stuck.
+ * Return ```None``` when no such graph exists, that is when *d* >= *n*
+ * or *n* * *d* is odd.
+ */
+pub fn random_regular<G, N, R, F>(rng: &mut R, n: usize, d: usize, weight: F) -> Option<G>
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    if (d >= n && n > 0) || !(n * d).is_multiple_of(2) {
+        return None;
+    }
+    let arcs = loop {
+        if let Some(arcs) = try_pairing(rng, n, d) {
+            break arcs;
+        }
+    };
+    Some(build(rng, n, GraphType::Undirect, arcs, weight))
+}
+
+fn try_pairing<R: Rng>(rng: &mut R, n: usize, d: usize) -> Option<Vec<(usize, usize)>> {
+    let mut arcs = vec![];
+    let mut present = HashSet::new();
+    let mut stubs: Vec<usize> = (0..n).flat_map(|i| std::iter::repeat_n(i, d)).collect();
+    while !stubs.is_empty() {
+        let mut unmatched = BTreeMap::new();
+        stubs.shuffle(rng);
+        for pair in stubs.chunks(2) {
+            let (i, j) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+            if i != j && present.insert((i, j)) {
+                arcs.push((i, j));
+            } else {
+                *unmatched.entry(i).or_insert(0) += 1;
+                *unmatched.entry(j).or_insert(0) += 1;
+            }
+        }
+        if !can_match(&unmatched, &present) {
+            return None;
+        }
+        stubs = unmatched
+            .into_iter()
+            .flat_map(|(i, count)| std::iter::repeat_n(i, count))
+            .collect();
+    }
+    Some(arcs)
+}
+
+fn can_match(unmatched: &BTreeMap<usize, usize>, present: &HashSet<(usize, usize)>) -> bool {
+    unmatched.is_empty()
+        || unmatched.keys().any(|i| {
+            unmatched
+                .keys()
+                .any(|j| i < j && !present.contains(&(*i, *j)))
+        })
+}
+
+fn build<G, N, R, F>(
+    rng: &mut R,
+    n: usize,
+    gtype: GraphType,
+    arcs: Vec<(usize, usize)>,
+    mut weight: F,
+) -> G
+where
+    G: Graph<N>,
+    R: Rng,
+    F: FnMut(&mut R, usize, usize) -> N,
+{
+    let mut graph = G::new(n, gtype);
+    for (i, j) in arcs {
+        let w = weight(rng, i, j);
+        graph.add_new_arc(i, j, w);
+    }
+    graph
+}
+
+/**
+ * Map an index in 0..n(n - 1) to the k-th arc (i, j), i != j.
+ */
+fn direct_pair(k: usize, n: usize) -> (usize, usize) {
+    let i = k / (n - 1);
+    let j = k % (n - 1);
+    let j = if j >= i { j + 1 } else { j };
+    (i, j)
+}
+
+/**
+ * Map an index in 0..n(n - 1)/2 to the k-th pair (i, j), i < j,
+ * in the order (0, 1), (0, 2), (1, 2), (0, 3), ...
+ */
+fn undirect_pair(k: usize) -> (usize, usize) {
+    let mut j = ((1.0 + (1.0 + 8.0 * k as f64).sqrt()) / 2.0) as usize;
+    while j * (j - 1) / 2 > k {
+        j -= 1;
+    }
+    while (j + 1) * j / 2 <= k {
+        j += 1;
+    }
+    (k - j * (j - 1) / 2, j)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::visitor::GraphVisitor;
+    use crate::{AdjList, MatrixGraph};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn unit<R: Rng>(_: &mut R, _: usize, _: usize) -> i32 {
+        1
+    }
+
+    fn degrees(graph: &AdjList<i32>) -> Vec<usize> {
+        let mut degrees = vec![0; graph.node_count()];
+        graph.arc_visitor(|i, _, _| degrees[i] += 1);
+        degrees
+    }
+
+    fn assert_simple(graph: &AdjList<i32>) {
+        let mut arcs = HashSet::new();
+        graph.arc_visitor(|i, j, _| {
+            assert_ne!(i, j);
+            assert!(arcs.insert((i, j)), "parallel arc ({i}, {j})");
+        });
+    }
+
+    #[test]
+    fn test_pair_index() {
+        let pairs: Vec<_> = (0..6).map(undirect_pair).collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 2), (0, 3), (1, 3), (2, 3)]);
+        let pairs: Vec<_> = (0..6).map(|k| direct_pair(k, 3)).collect();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn test_gnp() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let graph: AdjList<i32> = gnp(&mut rng, 20, 1.0, GraphType::Direct, unit);
+        assert_eq!((&graph).arc_count(), 20 * 19);
+        let graph: AdjList<i32> = gnp(&mut rng, 20, 0.0, GraphType::Undirect, unit);
+        assert_eq!((&graph).arc_count(), 0);
+
+        let graph: AdjList<i32> = gnp(&mut rng, 50, 0.3, GraphType::Undirect, unit);
+        assert_simple(&graph);
+        let mut same_rng = StdRng::seed_from_u64(1);
+        gnp::<AdjList<i32>, _, _, _>(&mut same_rng, 20, 1.0, GraphType::Direct, unit);
+        gnp::<AdjList<i32>, _, _, _>(&mut same_rng, 20, 0.0, GraphType::Undirect, unit);
+        let same: AdjList<i32> = gnp(&mut same_rng, 50, 0.3, GraphType::Undirect, unit);
+        assert_eq!(graph, same);
+    }
+
+    #[test]
+    fn test_gnm() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for gtype in [GraphType::Direct, GraphType::Undirect] {
+            let graph: AdjList<i32> = gnm(&mut rng, 30, 100, gtype, |_, i, j| (i + j) as i32);
+            assert_simple(&graph);
+            let expect = if gtype == GraphType::Direct { 100 } else { 200 };
+            assert_eq!((&graph).arc_count(), expect);
+            (&graph).arc_visitor(|i, j, w| assert_eq!(w, (i + j) as i32));
+        }
+        let graph: MatrixGraph<i32> = gnm(&mut rng, 5, 10, GraphType::Undirect, unit);
+        assert_eq!(graph.arc_iterator().count(), 20);
+    }
+
+    #[test]
+    fn test_barabasi_albert() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let graph: AdjList<i32> = barabasi_albert(&mut rng, 100, 3, unit);
+        assert_simple(&graph);
+        assert_eq!((&graph).arc_count(), 2 * 3 * 97);
+        assert!(degrees(&graph)[3..].iter().all(|d| *d >= 3));
+    }
+
+    #[test]
+    fn test_watts_strogatz() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let graph: AdjList<i32> = watts_strogatz(&mut rng, 20, 4, 0.0, unit);
+        assert!(degrees(&graph).iter().all(|d| *d == 4));
+        (&graph).arc_visitor(|i, j, _| {
+            let dist = (i + 20 - j) % 20;
+            assert!(dist.min(20 - dist) <= 2);
+        });
+
+        let graph: AdjList<i32> = watts_strogatz(&mut rng, 50, 6, 0.5, unit);
+        assert_simple(&graph);
+        assert_eq!((&graph).arc_count(), 50 * 6);
+    }
+
+    #[test]
+    fn test_random_regular() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let graph: AdjList<i32> = random_regular(&mut rng, 30, 3, unit).unwrap();
+        assert_simple(&graph);
+        assert!(degrees(&graph).iter().all(|d| *d == 3));
+
+        let graph: AdjList<i32> = random_regular(&mut rng, 10, 9, unit).unwrap();
+        assert_eq!((&graph).arc_count(), 90);
+
+        assert!(random_regular::<AdjList<i32>, _, _, _>(&mut rng, 5, 3, unit).is_none());
+        assert!(random_regular::<AdjList<i32>, _, _, _>(&mut rng, 5, 5, unit).is_none());
+    }
+}
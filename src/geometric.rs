@@ -0,0 +1,545 @@
+/*!
+ * Build graphs from sets of points in the plane, weighting
+ * each arc with the distance between its nodes according
+ * to a [Metric]. The *i*-th point becomes node *i*.
+ * Distances are converted to the weight type with
+ * [NumCast](num_traits::NumCast), so integer weights are truncated;
+ * the builders return [DistanceOutOfRange] when a distance
+ * does not fit the weight type.
+ * The k-nearest-neighbour and radius builders use a
+ * [kd-tree](https://en.wikipedia.org/wiki/K-d_tree) to avoid
+ * comparing every pair of points.
+ *
+ * ```
+ * use simplegraph::GraphType;
+ * use simplegraph::geometric::{knn_graph, Metric};
+ *
+ * let points = [(0.0, 0.0), (1.0, 0.0), (5.0, 0.0), (6.0, 0.0)];
+ * let graph = knn_graph::<f64>(&points, 1, Metric::Euclidean, GraphType::Direct).unwrap();
+ * let arcs: Vec<_> = graph.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 1.0), (1, 0, 1.0), (2, 3, 1.0), (3, 2, 1.0)]);
+ * ```
+ */
+
+use super::{AdjList, Graph, GraphType, MatrixGraph};
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+/**
+ * Mean Earth radius, in kilometers, used by [Metric::Haversine].
+ */
+pub const EARTH_RADIUS: f64 = 6371.0;
+
+/**
+ * Distance between two points.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Euclidean,
+    Manhattan,
+    /**
+     * Great-circle distance, in kilometers, between two points
+     * given as (latitude, longitude) in degrees.
+     */
+    Haversine,
+}
+
+impl Metric {
+    pub fn distance(&self, p: &(f64, f64), q: &(f64, f64)) -> f64 {
+        let (x1, y1) = p;
+        let (x2, y2) = q;
+        match self {
+            Self::Euclidean => (x1 - x2).hypot(y1 - y2),
+            Self::Manhattan => (x1 - x2).abs() + (y1 - y2).abs(),
+            Self::Haversine => {
+                let (lat1, lat2) = (x1.to_radians(), x2.to_radians());
+                let dlat = lat2 - lat1;
+                let dlon = (y2 - y1).to_radians();
+                let a = (dlat / 2.0).sin().powi(2)
+                    + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+                2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+            }
+        }
+    }
+
+    /**
+     * Map a point into the space searched by the kd-tree: the plane for
+     * Euclidean and Manhattan metrics, the unit sphere for haversine.
+     */
+    fn embed(&self, p: &(f64, f64)) -> [f64; 3] {
+        match self {
+            Self::Euclidean | Self::Manhattan => [p.0, p.1, 0.0],
+            Self::Haversine => {
+                let (lat, lon) = (p.0.to_radians(), p.1.to_radians());
+                [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+            }
+        }
+    }
+
+    /**
+     * Distance between embedded points, monotone with [Metric::distance].
+     */
+    fn embedded_distance(&self, p: &[f64; 3], q: &[f64; 3]) -> f64 {
+        let diff = p.iter().zip(q).map(|(a, b)| a - b);
+        match self {
+            Self::Manhattan => diff.map(f64::abs).sum(),
+            Self::Euclidean | Self::Haversine => diff.map(|d| d * d).sum::<f64>().sqrt(),
+        }
+    }
+
+    /**
+     * Convert a radius into the embedded space.
+     */
+    fn embedded_radius(&self, radius: f64) -> f64 {
+        match self {
+            Self::Euclidean | Self::Manhattan => radius,
+            Self::Haversine => {
+                let angle = (radius / EARTH_RADIUS).min(std::f64::consts::PI);
+                2.0 * (angle / 2.0).sin()
+            }
+        }
+    }
+}
+
+/**
+ * Error returned by the builders when the distance between
+ * two nodes cannot be represented by the weight type, for
+ * example a NaN coordinate or an overflowing distance.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistanceOutOfRange {
+    pub src: usize,
+    pub dst: usize,
+    pub distance: f64,
+}
+
+impl fmt::Display for DistanceOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "distance {} between nodes {} and {} cannot be represented",
+            self.distance, self.src, self.dst
+        )
+    }
+}
+
+impl Error for DistanceOutOfRange {}
+
+/**
+ * Build the complete graph on the given points: each
+ * pair of nodes is connected by an arc weighted with
+ * their distance, or return the first [DistanceOutOfRange].
+ */
+pub fn complete_graph<N>(
+    points: &[(f64, f64)],
+    metric: Metric,
+    gtype: GraphType,
+) -> Result<MatrixGraph<N>, DistanceOutOfRange>
+where
+    N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+{
+    let mut graph = MatrixGraph::new(points.len(), gtype);
+    for (i, p) in points.iter().enumerate() {
+        for (j, q) in points.iter().enumerate() {
+            if i != j && (gtype == GraphType::Direct || i < j) {
+                graph.add_new_arc(i, j, cast(metric.distance(p, q), i, j)?);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/**
+ * Build the k-nearest-neighbour graph on the given points: in a direct
+ * graph each node has an arc towards each of its *k* nearest nodes,
+ * in an undirect graph two nodes are connected when either is one
+ * of the *k* nearest nodes of the other. Ties are broken in favour
+ * of the node with the smallest index. Return the first
+ * [DistanceOutOfRange] if a distance does not fit *N*.
+ */
+pub fn knn_graph<N>(
+    points: &[(f64, f64)],
+    k: usize,
+    metric: Metric,
+    gtype: GraphType,
+) -> Result<AdjList<N>, DistanceOutOfRange>
+where
+    N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+{
+    let tree = KdTree::new(points, metric);
+    let mut graph = AdjList::new(points.len(), gtype);
+    let mut inserted = HashSet::new();
+    for (i, p) in points.iter().enumerate() {
+        for j in tree.nearest(i, k) {
+            let key = match gtype {
+                GraphType::Direct => (i, j),
+                GraphType::Undirect => (i.min(j), i.max(j)),
+            };
+            if inserted.insert(key) {
+                graph.add_new_arc(i, j, cast(metric.distance(p, &points[j]), i, j)?);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/**
+ * Build the graph connecting each pair of distinct nodes whose distance
+ * is at most *radius*. The relation is symmetric: a direct graph
+ * gets arcs in both directions. Return the first
+ * [DistanceOutOfRange] if a distance does not fit *N*.
+ */
+pub fn radius_graph<N>(
+    points: &[(f64, f64)],
+    radius: f64,
+    metric: Metric,
+    gtype: GraphType,
+) -> Result<AdjList<N>, DistanceOutOfRange>
+where
+    N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+{
+    let tree = KdTree::new(points, metric);
+    let mut graph = AdjList::new(points.len(), gtype);
+    for (i, p) in points.iter().enumerate() {
+        for j in tree.within(i, radius) {
+            if gtype == GraphType::Direct || i < j {
+                graph.add_new_arc(i, j, cast(metric.distance(p, &points[j]), i, j)?);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/**
+ * Random geometric graph: place *n* points uniformly at random in the unit
+ * square and connect them using [radius_graph]. Return the graph and the points,
+ * or the first [DistanceOutOfRange] if a distance does not fit *N*.
+ */
+pub fn random_geometric<N, R>(
+    rng: &mut R,
+    n: usize,
+    radius: f64,
+    metric: Metric,
+    gtype: GraphType,
+) -> Result<RandomGeometric<N>, DistanceOutOfRange>
+where
+    N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+    R: Rng,
+{
+    let points: Vec<_> = (0..n).map(|_| (rng.gen(), rng.gen())).collect();
+    let graph = radius_graph(&points, radius, metric, gtype)?;
+    Ok((graph, points))
+}
+
+type RandomGeometric<N> = (AdjList<N>, Vec<(f64, f64)>);
+
+fn cast<N: num_traits::NumCast>(
+    distance: f64,
+    src: usize,
+    dst: usize,
+) -> Result<N, DistanceOutOfRange> {
+    N::from(distance).ok_or(DistanceOutOfRange { src, dst, distance })
+}
+
+/**
+ * Static kd-tree stored as an implicit binary tree: the root of
+ * each sub slice is its middle element, split along the axis
+ * given by its depth.
+ */
+struct KdTree {
+    metric: Metric,
+    points: Vec<[f64; 3]>,
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    fn new(points: &[(f64, f64)], metric: Metric) -> Self {
+        let points: Vec<_> = points.iter().map(|p| metric.embed(p)).collect();
+        let mut order: Vec<_> = (0..points.len()).collect();
+        let dims = Self::dims(metric);
+        Self::build(&points, &mut order, 0, dims);
+        Self {
+            metric,
+            points,
+            order,
+        }
+    }
+
+    fn dims(metric: Metric) -> usize {
+        match metric {
+            Metric::Euclidean | Metric::Manhattan => 2,
+            Metric::Haversine => 3,
+        }
+    }
+
+    fn build(points: &[[f64; 3]], order: &mut [usize], depth: usize, dims: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let axis = depth % dims;
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by(mid, |a, b| points[*a][axis].total_cmp(&points[*b][axis]));
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(points, left, depth + 1, dims);
+        Self::build(points, &mut right[1..], depth + 1, dims);
+    }
+
+    /**
+     * The *k* points nearest to point *i*, excluding *i* itself,
+     * sorted by distance and index.
+     */
+    fn nearest(&self, i: usize, k: usize) -> Vec<usize> {
+        let mut best = Vec::with_capacity(k + 1);
+        if k > 0 {
+            self.search_nearest(i, k, 0, self.order.len(), 0, &mut best);
+        }
+        best.into_iter().map(|(_, j)| j).collect()
+    }
+
+    fn search_nearest(
+        &self,
+        i: usize,
+        k: usize,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut Vec<(f64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let node = self.order[mid];
+        let query = &self.points[i];
+        if node != i {
+            let candidate = (
+                self.metric.embedded_distance(query, &self.points[node]),
+                node,
+            );
+            let pos = best.partition_point(|b| Self::before(b, &candidate));
+            if pos < k {
+                best.insert(pos, candidate);
+                best.truncate(k);
+            }
+        }
+        let axis = depth % Self::dims(self.metric);
+        let diff = query[axis] - self.points[node][axis];
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search_nearest(i, k, near.0, near.1, depth + 1, best);
+        if best.len() < k || diff.abs() <= best[best.len() - 1].0 {
+            self.search_nearest(i, k, far.0, far.1, depth + 1, best);
+        }
+    }
+
+    fn before(a: &(f64, usize), b: &(f64, usize)) -> bool {
+        a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).is_lt()
+    }
+
+    /**
+     * The points, other than *i*, within *radius* from point *i*, sorted by index.
+     */
+    fn within(&self, i: usize, radius: f64) -> Vec<usize> {
+        let radius = self.metric.embedded_radius(radius);
+        let mut found = vec![];
+        self.search_within(i, radius, 0, self.order.len(), 0, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    fn search_within(
+        &self,
+        i: usize,
+        radius: f64,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        found: &mut Vec<usize>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let node = self.order[mid];
+        let query = &self.points[i];
+        if node != i && self.metric.embedded_distance(query, &self.points[node]) <= radius {
+            found.push(node);
+        }
+        let axis = depth % Self::dims(self.metric);
+        let diff = query[axis] - self.points[node][axis];
+        if diff <= radius {
+            self.search_within(i, radius, lo, mid, depth + 1, found);
+        }
+        if -diff <= radius {
+            self.search_within(i, radius, mid + 1, hi, depth + 1, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::tests;
+    use crate::visitor::GraphVisitor;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn random_points(n: usize, seed: u64) -> Vec<(f64, f64)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n).map(|_| (rng.gen(), rng.gen())).collect()
+    }
+
+    fn brute_nearest(points: &[(f64, f64)], metric: Metric, i: usize, k: usize) -> Vec<usize> {
+        let mut others: Vec<_> = (0..points.len())
+            .filter(|j| *j != i)
+            .map(|j| (metric.distance(&points[i], &points[j]), j))
+            .collect();
+        others.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        others.into_iter().take(k).map(|(_, j)| j).collect()
+    }
+
+    #[test]
+    fn test_metric() {
+        let p = (0.0, 0.0);
+        let q = (3.0, 4.0);
+        tests::approx_equal(Metric::Euclidean.distance(&p, &q), 5.0, 1e-12);
+        tests::approx_equal(Metric::Manhattan.distance(&p, &q), 7.0, 1e-12);
+
+        let rome = (41.9028, 12.4964);
+        let paris = (48.8566, 2.3522);
+        tests::approx_equal(Metric::Haversine.distance(&rome, &paris), 1105.8, 1.0);
+        let antipode = (-41.9028, 12.4964 - 180.0);
+        let half_circle = std::f64::consts::PI * EARTH_RADIUS;
+        tests::approx_equal(
+            Metric::Haversine.distance(&rome, &antipode),
+            half_circle,
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_complete_graph() {
+        let points = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)];
+        let graph: MatrixGraph<f64> =
+            complete_graph(&points, Metric::Manhattan, GraphType::Undirect).unwrap();
+        let arcs: Vec<_> = graph.arc_iterator().collect();
+        let expect = vec![
+            (0, 1, 1.0),
+            (0, 2, 2.0),
+            (1, 0, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 2.0),
+            (2, 1, 1.0),
+        ];
+        assert_eq!(arcs, expect);
+
+        let graph: MatrixGraph<u32> =
+            complete_graph(&points, Metric::Euclidean, GraphType::Direct).unwrap();
+        assert_eq!(graph.arc_iterator().count(), 6);
+        // integer weights are truncated
+        assert!(graph.arc_iterator().any(|arc| arc == (0, 2, 1)));
+    }
+
+    #[test]
+    fn test_distance_out_of_range() {
+        let points = [(0.0, 0.0), (0.0, 400.0), (0.0, 1.0), (0.0, 401.0)];
+        let err = complete_graph::<u8>(&points, Metric::Euclidean, GraphType::Undirect)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            DistanceOutOfRange {
+                src: 0,
+                dst: 1,
+                distance: 400.0
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "distance 400 between nodes 0 and 1 cannot be represented"
+        );
+        assert!(knn_graph::<u8>(&points, 1, Metric::Euclidean, GraphType::Direct).is_ok());
+        assert!(radius_graph::<u8>(&points, 500.0, Metric::Euclidean, GraphType::Direct).is_err());
+
+        let points = [(0.0, 0.0), (f64::NAN, 1.0)];
+        let err = complete_graph::<i32>(&points, Metric::Euclidean, GraphType::Direct)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "distance NaN between nodes 0 and 1 cannot be represented"
+        );
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = random_points(200, 7);
+        for metric in [Metric::Euclidean, Metric::Manhattan, Metric::Haversine] {
+            let tree = KdTree::new(&points, metric);
+            for i in (0..points.len()).step_by(13) {
+                let expect = brute_nearest(&points, metric, i, 5);
+                assert_eq!(tree.nearest(i, 5), expect, "{metric:?} node {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_knn_graph() {
+        let points = random_points(100, 8);
+        let graph = knn_graph::<f64>(&points, 3, Metric::Euclidean, GraphType::Direct).unwrap();
+        let mut out_degree = vec![0; points.len()];
+        (&graph).arc_visitor(|i, _, _| out_degree[i] += 1);
+        assert!(out_degree.iter().all(|d| *d == 3));
+
+        let graph = knn_graph::<f64>(&points, 3, Metric::Euclidean, GraphType::Undirect).unwrap();
+        let mut arcs = HashSet::new();
+        (&graph).arc_visitor(|i, j, w| {
+            assert!(arcs.insert((i, j)));
+            tests::approx_equal(w, Metric::Euclidean.distance(&points[i], &points[j]), 1e-12);
+        });
+        for i in 0..points.len() {
+            for j in brute_nearest(&points, Metric::Euclidean, i, 3) {
+                assert!(arcs.contains(&(i, j)) && arcs.contains(&(j, i)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_radius_graph() {
+        let points = random_points(150, 9);
+        for metric in [Metric::Euclidean, Metric::Manhattan, Metric::Haversine] {
+            let radius = match metric {
+                Metric::Haversine => 20.0,
+                _ => 0.15,
+            };
+            let graph = radius_graph::<f64>(&points, radius, metric, GraphType::Direct).unwrap();
+            let mut arcs = HashSet::new();
+            (&graph).arc_visitor(|i, j, _| {
+                arcs.insert((i, j));
+            });
+            for (i, p) in points.iter().enumerate() {
+                for (j, q) in points.iter().enumerate() {
+                    let close = i != j && metric.distance(p, q) <= radius;
+                    assert_eq!(arcs.contains(&(i, j)), close, "{metric:?} ({i}, {j})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_geometric() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let (graph, points) =
+            random_geometric::<f64, _>(&mut rng, 50, 0.2, Metric::Euclidean, GraphType::Undirect)
+                .unwrap();
+        assert_eq!(points.len(), 50);
+        (&graph).arc_visitor(|_, _, w| assert!(w <= 0.2));
+    }
+}
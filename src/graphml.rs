@@ -0,0 +1,512 @@
+/*!
+ * Read and write graphs in the [GraphML](http://graphml.graphdrawing.org/)
+ * format, understood by tools like Gephi, yEd and NetworkX.
+ * Node and arc weights are stored as `<data>` elements whose
+ * key has `attr.name="weight"`, while the [GraphType]
+ * is mapped to the `edgedefault` attribute of the graph.
+ */
+
+use super::read_error::ReadError;
+use super::visitor;
+use super::{GetGraphType, Graph, GraphType};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+const NODE_KEY: &str = "d0";
+const ARC_KEY: &str = "d1";
+
+/**
+ * Weight types that can be stored in a GraphML file.
+ * Associate each type with its GraphML `attr.type`.
+ */
+pub trait GraphMLType {
+    const ATTR_TYPE: &'static str;
+}
+
+macro_rules! graphml_type {
+    ($attr:expr, $($t:ty),*) => {
+        $(
+            impl GraphMLType for $t {
+                const ATTR_TYPE: &'static str = $attr;
+            }
+        )*
+    };
+}
+
+graphml_type!("int", i8, i16, i32, u8, u16);
+graphml_type!("long", i64, i128, isize, u32, u64, u128, usize);
+graphml_type!("float", f32);
+graphml_type!("double", f64);
+
+/**
+ * Write the given [GraphVisitor](visitor::GraphVisitor) into *writer*
+ * as a GraphML document. In undirect graphs each arc is written once.
+ */
+pub fn write_graphml<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy + fmt::Display + GraphMLType,
+{
+    let (edge_default, undirect) = match g.graph_type() {
+        GraphType::Direct => ("directed", false),
+        GraphType::Undirect => ("undirected", true),
+    };
+    let attr_type = N::ATTR_TYPE;
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    writeln!(
+        writer,
+        "  <key id=\"{NODE_KEY}\" for=\"node\" attr.name=\"weight\" attr.type=\"{attr_type}\"/>"
+    )?;
+    writeln!(
+        writer,
+        "  <key id=\"{ARC_KEY}\" for=\"edge\" attr.name=\"weight\" attr.type=\"{attr_type}\"/>"
+    )?;
+    writeln!(writer, "  <graph id=\"G\" edgedefault=\"{edge_default}\">")?;
+
+    let mut status = Ok(());
+    g.node_visitor(|i, n| {
+        if status.is_ok() {
+            status = writeln!(
+                writer,
+                "    <node id=\"n{i}\"><data key=\"{NODE_KEY}\">{n}</data></node>"
+            );
+        }
+    });
+    g.arc_visitor(|i, j, n| {
+        if status.is_ok() && (!undirect || i <= j) {
+            status = writeln!(
+                writer,
+                "    <edge source=\"n{i}\" target=\"n{j}\"><data key=\"{ARC_KEY}\">{n}</data></edge>"
+            );
+        }
+    });
+    status?;
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+/**
+ * Convert the given [GraphVisitor](visitor::GraphVisitor)
+ * into a GraphML document.
+ */
+pub fn to_graphml<G, N>(g: G) -> String
+where
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy + fmt::Display + GraphMLType,
+{
+    let mut buff = vec![];
+    write_graphml(&mut buff, g).expect("writing into a Vec cannot fail");
+    String::from_utf8(buff).expect("GraphML documents are always valid UTF-8")
+}
+
+/**
+ * Read a graph from a GraphML document. Nodes are numbered
+ * in the order they appear in the document. Weights are taken
+ * from the `<data>` elements whose key has `attr.name="weight"`;
+ * missing weights are set to the key's `<default>`, if any,
+ * or to [Default::default()].
+ *
+ * ```
+ * use simplegraph::{AdjList, GraphVisitor};
+ * use simplegraph::graphml::read_graphml;
+ *
+ * let doc = r#"<graphml>
+ *   <key id="w" for="edge" attr.name="weight" attr.type="double"/>
+ *   <graph edgedefault="directed">
+ *     <node id="a"/>
+ *     <node id="b"/>
+ *     <edge source="a" target="b"><data key="w">2.5</data></edge>
+ *   </graph>
+ * </graphml>"#;
+ * let graph: AdjList<f64> = read_graphml(doc.as_bytes()).unwrap();
+ * assert_eq!((&graph).node_count(), 2);
+ * assert_eq!(graph.arc_iterator().collect::<Vec<_>>(), vec![(0, 1, 2.5)]);
+ * ```
+ */
+pub fn read_graphml<R, G, N>(mut reader: R) -> Result<G, ReadError>
+where
+    R: io::Read,
+    G: Graph<N>,
+    N: num_traits::Num + Default + Copy + FromStr,
+{
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+    from_graphml(&source)
+}
+
+/**
+ * Like [read_graphml], parse the given GraphML document.
+ */
+pub fn from_graphml<G, N>(source: &str) -> Result<G, ReadError>
+where
+    G: Graph<N>,
+    N: num_traits::Num + Default + Copy + FromStr,
+{
+    let doc = GraphMLParser::new(source).parse()?;
+    let mut graph = G::new(doc.nodes.len(), doc.gtype);
+    let node_default = doc.node_weight.default;
+    let arc_default = doc.arc_weight.default;
+    graph.update_all_nodes_weight(|i, _| doc.nodes[i].unwrap_or(node_default));
+    for (i, j, w) in doc.arcs {
+        graph.add_new_arc(i, j, w.unwrap_or(arc_default));
+    }
+    Ok(graph)
+}
+
+struct WeightKey<N> {
+    id: Option<String>,
+    default: N,
+}
+
+impl<N: Default> Default for WeightKey<N> {
+    fn default() -> Self {
+        Self {
+            id: None,
+            default: N::default(),
+        }
+    }
+}
+
+enum Element {
+    Node(usize),
+    Arc(usize),
+    Key(String),
+    Other,
+}
+
+struct Document<N> {
+    gtype: GraphType,
+    nodes: Vec<Option<N>>,
+    arcs: Vec<(usize, usize, Option<N>)>,
+    node_weight: WeightKey<N>,
+    arc_weight: WeightKey<N>,
+}
+
+struct GraphMLParser<'a, N> {
+    source: &'a str,
+    reader: Reader<&'a [u8]>,
+    node_ids: HashMap<String, usize>,
+    pending_arcs: Vec<(String, String, Option<N>, usize)>,
+    nodes: Vec<Option<N>>,
+    node_weight: WeightKey<N>,
+    arc_weight: WeightKey<N>,
+    gtype: Option<GraphType>,
+    element: Element,
+    data_key: Option<String>,
+    in_default: bool,
+}
+
+impl<'a, N> GraphMLParser<'a, N>
+where
+    N: num_traits::Num + Default + Copy + FromStr,
+{
+    fn new(source: &'a str) -> Self {
+        let mut reader = Reader::from_str(source);
+        reader.trim_text(true);
+        Self {
+            source,
+            reader,
+            node_ids: HashMap::new(),
+            pending_arcs: vec![],
+            nodes: vec![],
+            node_weight: WeightKey::default(),
+            arc_weight: WeightKey::default(),
+            gtype: None,
+            element: Element::Other,
+            data_key: None,
+            in_default: false,
+        }
+    }
+
+    fn parse(mut self) -> Result<Document<N>, ReadError> {
+        loop {
+            let event = self
+                .reader
+                .read_event()
+                .map_err(|err| self.error(err.to_string()))?;
+            match event {
+                Event::Start(tag) => self.open_tag(&tag, false)?,
+                Event::Empty(tag) => self.open_tag(&tag, true)?,
+                Event::End(tag) => {
+                    let name = tag.local_name();
+                    self.close_tag(name.as_ref());
+                }
+                Event::Text(text) => {
+                    let text = text.unescape().map_err(|err| self.error(err.to_string()))?;
+                    self.set_text(&text)?;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        self.build()
+    }
+
+    fn open_tag(&mut self, tag: &BytesStart, empty: bool) -> Result<(), ReadError> {
+        match tag.local_name().as_ref() {
+            b"key" => {
+                let id = self.required_attribute(tag, "key", "id")?;
+                let target = self
+                    .attribute(tag, "for")?
+                    .unwrap_or_else(|| "all".to_owned());
+                let name = self.attribute(tag, "attr.name")?.unwrap_or_default();
+                if name == "weight" {
+                    if target == "node" || target == "all" {
+                        self.node_weight.id = Some(id.clone());
+                    }
+                    if target == "edge" || target == "all" {
+                        self.arc_weight.id = Some(id.clone());
+                    }
+                }
+                if !empty {
+                    self.element = Element::Key(id);
+                }
+            }
+            b"default" => self.in_default = !empty,
+            b"graph" => {
+                if self.gtype.is_some() {
+                    return Err(self.error("nested or multiple graphs are not supported"));
+                }
+                let gtype = match self.attribute(tag, "edgedefault")?.as_deref() {
+                    Some("directed") => GraphType::Direct,
+                    Some("undirected") => GraphType::Undirect,
+                    Some(other) => {
+                        return Err(self.error(format!("unknown edgedefault \"{other}\"")))
+                    }
+                    None => return Err(self.error("graph element without edgedefault")),
+                };
+                self.gtype = Some(gtype);
+            }
+            b"node" => {
+                let id = self.required_attribute(tag, "node", "id")?;
+                let index = self.nodes.len();
+                if self.node_ids.insert(id.clone(), index).is_some() {
+                    return Err(self.error(format!("duplicate node id \"{id}\"")));
+                }
+                self.nodes.push(None);
+                if !empty {
+                    self.element = Element::Node(index);
+                }
+            }
+            b"edge" => {
+                let source = self.required_attribute(tag, "edge", "source")?;
+                let target = self.required_attribute(tag, "edge", "target")?;
+                let index = self.pending_arcs.len();
+                let pos = self.reader.buffer_position();
+                self.pending_arcs.push((source, target, None, pos));
+                if !empty {
+                    self.element = Element::Arc(index);
+                }
+            }
+            b"data" if !empty => {
+                self.data_key = Some(self.required_attribute(tag, "data", "key")?);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn close_tag(&mut self, name: &[u8]) {
+        match name {
+            b"node" | b"edge" | b"key" => self.element = Element::Other,
+            b"data" => self.data_key = None,
+            b"default" => self.in_default = false,
+            _ => {}
+        }
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), ReadError> {
+        match (&self.element, &self.data_key) {
+            (Element::Key(id), _) if self.in_default => {
+                if self.node_weight.id.as_ref() == Some(id) {
+                    self.node_weight.default = self.parse_weight(text)?;
+                }
+                if self.arc_weight.id.as_ref() == Some(id) {
+                    self.arc_weight.default = self.parse_weight(text)?;
+                }
+            }
+            (Element::Node(index), Some(key)) if self.node_weight.id.as_ref() == Some(key) => {
+                let index = *index;
+                self.nodes[index] = Some(self.parse_weight(text)?);
+            }
+            (Element::Arc(index), Some(key)) if self.arc_weight.id.as_ref() == Some(key) => {
+                let index = *index;
+                self.pending_arcs[index].2 = Some(self.parse_weight(text)?);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn build(self) -> Result<Document<N>, ReadError> {
+        let gtype = self
+            .gtype
+            .ok_or_else(|| ReadError::syntax(self.line(), "missing graph element"))?;
+        let mut arcs = Vec::with_capacity(self.pending_arcs.len());
+        for (source, target, weight, pos) in &self.pending_arcs {
+            let i = self.node_index(source, *pos)?;
+            let j = self.node_index(target, *pos)?;
+            arcs.push((i, j, *weight));
+        }
+        Ok(Document {
+            gtype,
+            nodes: self.nodes,
+            arcs,
+            node_weight: self.node_weight,
+            arc_weight: self.arc_weight,
+        })
+    }
+
+    fn node_index(&self, id: &str, pos: usize) -> Result<usize, ReadError> {
+        self.node_ids.get(id).copied().ok_or_else(|| {
+            let message = format!("edge refers to unknown node \"{id}\"");
+            ReadError::syntax(self.line_at(pos), message)
+        })
+    }
+
+    fn parse_weight(&self, text: &str) -> Result<N, ReadError> {
+        text.trim()
+            .parse()
+            .map_err(|_| self.error(format!("invalid weight \"{text}\"")))
+    }
+
+    fn attribute(&self, tag: &BytesStart, name: &str) -> Result<Option<String>, ReadError> {
+        let attr = tag
+            .try_get_attribute(name)
+            .map_err(|err| self.error(err.to_string()))?;
+        match attr {
+            Some(attr) => {
+                let value = attr
+                    .unescape_value()
+                    .map_err(|err| self.error(err.to_string()))?;
+                Ok(Some(value.into_owned()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn required_attribute(
+        &self,
+        tag: &BytesStart,
+        element: &str,
+        name: &str,
+    ) -> Result<String, ReadError> {
+        self.attribute(tag, name)?
+            .ok_or_else(|| self.error(format!("{element} element without {name} attribute")))
+    }
+
+    /**
+     * Line of the current reader position. Scans the source from the
+     * start, so call it only when building an error.
+     */
+    fn line(&self) -> usize {
+        self.line_at(self.reader.buffer_position())
+    }
+
+    fn line_at(&self, pos: usize) -> usize {
+        let pos = pos.min(self.source.len());
+        self.source.as_bytes()[..pos]
+            .iter()
+            .filter(|c| **c == b'\n')
+            .count()
+            + 1
+    }
+
+    fn error<S: Into<String>>(&self, message: S) -> ReadError {
+        ReadError::syntax(self.line(), message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::{AdjList, MatrixGraph};
+
+    #[test]
+    fn test_write_graphml() {
+        let mut graph = AdjList::new_undirect(3);
+        graph.update_all_nodes_weight(|i, _| i as i32);
+        graph.add_new_arc(0, 1, 5);
+        graph.add_new_arc(2, 1, 7);
+
+        let expect = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">
+  <key id=\"d0\" for=\"node\" attr.name=\"weight\" attr.type=\"int\"/>
+  <key id=\"d1\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>
+  <graph id=\"G\" edgedefault=\"undirected\">
+    <node id=\"n0\"><data key=\"d0\">0</data></node>
+    <node id=\"n1\"><data key=\"d0\">1</data></node>
+    <node id=\"n2\"><data key=\"d0\">2</data></node>
+    <edge source=\"n0\" target=\"n1\"><data key=\"d1\">5</data></edge>
+    <edge source=\"n1\" target=\"n2\"><data key=\"d1\">7</data></edge>
+  </graph>
+</graphml>
+";
+        assert_eq!(to_graphml(&graph), expect);
+    }
+
+    #[test]
+    fn test_graphml_round_trip() {
+        let mut graph = AdjList::new_direct(4);
+        graph.update_all_nodes_weight(|i, _| 0.5 * i as f64);
+        graph.add_new_arc(0, 1, 1.5);
+        graph.add_new_arc(1, 2, -2.5);
+        graph.add_new_arc(3, 0, 4.0);
+
+        let doc = to_graphml(&graph);
+        let new_graph: AdjList<f64> = from_graphml(&doc).unwrap();
+        assert_eq!(graph, new_graph);
+
+        let matrix: MatrixGraph<f64> = from_graphml(&doc).unwrap();
+        assert_eq!(to_graphml(&matrix), doc);
+    }
+
+    #[test]
+    fn test_read_graphml_defaults() {
+        let doc = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="w" for="all" attr.name="weight" attr.type="long">
+    <default>3</default>
+  </key>
+  <graph id="G" edgedefault="undirected">
+    <edge source="b" target="a"/>
+    <node id="a"><data key="label">A</data></node>
+    <node id="b"><data key="w">7</data></node>
+    <node id="c"/>
+    <edge source="b" target="c"><data key="w">9</data></edge>
+  </graph>
+</graphml>"#;
+        let graph: AdjList<i64> = from_graphml(doc).unwrap();
+        let nodes: Vec<_> = graph.node_iterator().collect();
+        assert_eq!(nodes, vec![(0, 3), (1, 7), (2, 3)]);
+        let arcs: Vec<_> = graph.arc_iterator().collect();
+        assert_eq!(arcs, vec![(0, 1, 3), (1, 0, 3), (1, 2, 9), (2, 1, 9)]);
+    }
+
+    #[test]
+    fn test_read_graphml_errors() {
+        let doc = "<graphml>\n<graph edgedefault=\"directed\">\n<node id=\"a\"/>\n<edge source=\"a\" target=\"z\"/>\n</graph>\n</graphml>";
+        let err = from_graphml::<AdjList<i32>, i32>(doc).unwrap_err();
+        assert!(matches!(err, ReadError::Syntax { line: 4, .. }), "{err}");
+
+        let doc = "<graphml>\n<graph>\n</graph>\n</graphml>";
+        let err = from_graphml::<AdjList<i32>, i32>(doc).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: graph element without edgedefault");
+
+        let doc = "<graphml>\n<key id=\"w\" for=\"node\" attr.name=\"weight\"/>\n<graph edgedefault=\"directed\">\n<node id=\"a\"><data key=\"w\">x</data></node>\n</graph>\n</graphml>";
+        let err = from_graphml::<AdjList<i32>, i32>(doc).unwrap_err();
+        assert!(matches!(err, ReadError::Syntax { line: 4, .. }), "{err}");
+    }
+}
@@ -9,12 +9,30 @@ macro_rules! enum_mut {
 }
 
 pub mod adjacency_list_graph;
+pub mod binary;
+pub mod contraction;
+pub mod contraction_hierarchy;
+pub mod dimacs;
 pub mod dot;
+pub mod edge_list;
+pub mod families;
+pub mod generators;
+pub mod geometric;
 pub mod graph;
+pub mod graphml;
 pub mod math_graph;
+pub mod matrix_market;
 pub mod matrix_graph;
 pub mod path_cost;
+pub mod products;
+pub mod read_error;
+pub mod set_ops;
+pub mod shortest_path;
+pub mod transitive;
+pub mod tsp;
+pub mod tsplib;
 mod update_nodes;
+pub mod views;
 pub mod visitor;
 
 use serde::{Deserialize, Serialize};
@@ -47,14 +65,6 @@ fn empty_list_of_lists<T>(count: usize) -> Vec<Vec<T>> {
 
 #[cfg(test)]
 mod tests {
-    pub fn euclid_distance(p1: &(f64, f64), p2: &(f64, f64)) -> f64 {
-        let (x1, y1) = p1;
-        let (x2, y2) = p2;
-        let dx = x1 - x2;
-        let dy = y1 - y2;
-        ((dx * dx) + (dy * dy)).sqrt()
-    }
-
     pub fn approx_equal(a: f64, b: f64, tol: f64) {
         let diff = (a - b).abs();
         assert!(diff <= tol, "a: {a}, b: {b}, tol: {tol}");
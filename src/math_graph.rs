@@ -6,23 +6,247 @@ use super::graph;
 use super::update_nodes;
 use super::{GetGraphType, GraphType};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
 
+/**
+ * Deserialized graphs are validated before being accepted:
+ * an arc or a compact node referring to a non existing node
+ * makes deserialization fail with a [InvalidGraph] message
+ * instead of panicking while building the graph.
+ */
 #[derive(Deserialize, Serialize)]
+#[serde(try_from = "UncheckedMathGraph<N>")]
 pub struct MathGraph<N>
 where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    header: Option<Header>,
     gtype: GraphType,
     nodes: Nodes<N>,
     arcs: Arcs<N>,
 }
 
+#[derive(Deserialize)]
+struct UncheckedMathGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    header: Option<Header>,
+    gtype: GraphType,
+    nodes: Nodes<N>,
+    arcs: Arcs<N>,
+}
+
+impl<N> TryFrom<UncheckedMathGraph<N>> for MathGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    type Error = InvalidGraph;
+
+    fn try_from(g: UncheckedMathGraph<N>) -> Result<Self, Self::Error> {
+        let mut graph = Self::new(g.nodes, g.arcs, g.gtype);
+        graph.header = g.header;
+        graph.validate()?;
+        Ok(graph)
+    }
+}
+
+/**
+ * Version of the serialization format written by this crate.
+ * Files without a [Header] predate versioning and are read as version 0.
+ */
+pub const FORMAT_VERSION: u32 = 1;
+
+/**
+ * Graph representation a [MathGraph] was created from.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Representation {
+    AdjList,
+    MatrixGraph,
+}
+
+/**
+ * Optional self-describing header of a serialized graph: the format version,
+ * the original representation, a name and free-form key/value attributes.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Header {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    representation: Option<Representation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, String>,
+}
+
+impl Header {
+    pub fn new() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            representation: None,
+            name: None,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn representation(&self) -> Option<Representation> {
+        self.representation
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Describe why a [MathGraph] does not represent a valid graph.
+ */
+#[derive(Debug, PartialEq)]
+pub enum InvalidGraph {
+    /**
+     * A compact node weight refers to a node out of range.
+     */
+    NodeOutOfRange { node: usize, node_count: usize },
+    /**
+     * An arc refers to a node out of range.
+     */
+    ArcOutOfRange {
+        src: usize,
+        dst: usize,
+        node_count: usize,
+    },
+    /**
+     * The header declares a format version newer than [FORMAT_VERSION].
+     */
+    UnsupportedVersion { version: u32 },
+}
+
+impl fmt::Display for InvalidGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NodeOutOfRange { node, node_count } => write!(
+                f,
+                "node {node} out of range: the graph has {node_count} nodes"
+            ),
+            Self::ArcOutOfRange {
+                src,
+                dst,
+                node_count,
+            } => write!(
+                f,
+                "arc ({src}, {dst}) out of range: the graph has {node_count} nodes"
+            ),
+            Self::UnsupportedVersion { version } => write!(
+                f,
+                "unsupported format version {version}: the latest supported is {FORMAT_VERSION}"
+            ),
+        }
+    }
+}
+
+impl Error for InvalidGraph {}
+
 impl<N> MathGraph<N>
 where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
 {
     pub fn new(nodes: Nodes<N>, arcs: Arcs<N>, gtype: GraphType) -> Self {
-        Self { nodes, arcs, gtype }
+        Self {
+            header: None,
+            nodes,
+            arcs,
+            gtype,
+        }
+    }
+
+    /**
+     * Set the header, replacing the existing one.
+     */
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /**
+     * Set the original representation, creating the header if missing.
+     */
+    pub fn with_representation(mut self, representation: Representation) -> Self {
+        self.header_mut().representation = Some(representation);
+        self
+    }
+
+    /**
+     * Set the graph name, creating the header if missing.
+     */
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.header_mut().name = Some(name.into());
+        self
+    }
+
+    /**
+     * Add a key/value attribute, creating the header if missing.
+     */
+    pub fn with_attribute<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.header_mut()
+            .attributes
+            .insert(key.into(), value.into());
+        self
+    }
+
+    fn header_mut(&mut self) -> &mut Header {
+        self.header.get_or_insert_with(Header::new)
+    }
+
+    pub fn header(&self) -> Option<&Header> {
+        self.header.as_ref()
+    }
+
+    /**
+     * The format version, 0 for graphs without header.
+     */
+    pub fn version(&self) -> u32 {
+        self.header.as_ref().map_or(0, Header::version)
+    }
+
+    pub fn representation(&self) -> Option<Representation> {
+        self.header.as_ref().and_then(Header::representation)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.header.as_ref().and_then(Header::name)
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.header.as_ref().and_then(|h| h.attribute(key))
     }
 
     pub fn node_count(&self) -> usize {
@@ -36,6 +260,39 @@ where
     pub fn dismount(self) -> (Nodes<N>, Arcs<N>) {
         (self.nodes, self.arcs)
     }
+
+    /**
+     * Check that every node and arc refers to an existing node.
+     */
+    pub fn validate(&self) -> Result<(), InvalidGraph> {
+        let version = self.version();
+        if version > FORMAT_VERSION {
+            return Err(InvalidGraph::UnsupportedVersion { version });
+        }
+        let node_count = self.node_count();
+        if let Nodes::Compact(compact) = &self.nodes {
+            if let Some((node, _)) = compact.weights.iter().find(|(i, _)| *i >= node_count) {
+                let node = *node;
+                return Err(InvalidGraph::NodeOutOfRange { node, node_count });
+            }
+        }
+        let out_of_range = |i: usize, j: usize| i >= node_count || j >= node_count;
+        let invalid = match &self.arcs {
+            Arcs::Simple(simple) => simple.iter().copied().find(|(i, j)| out_of_range(*i, *j)),
+            Arcs::Weighted(weighted) => weighted
+                .iter()
+                .map(|(i, j, _)| (*i, *j))
+                .find(|(i, j)| out_of_range(*i, *j)),
+        };
+        match invalid {
+            Some((src, dst)) => Err(InvalidGraph::ArcOutOfRange {
+                src,
+                dst,
+                node_count,
+            }),
+            None => Ok(()),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize)]
@@ -82,10 +339,10 @@ where
     }
 }
 
-fn count_zeros<'a, I, N: 'a>(iter: I) -> usize
+fn count_zeros<'a, I, N>(iter: I) -> usize
 where
     I: Iterator<Item = &'a N>,
-    N: num_traits::Num,
+    N: num_traits::Num + 'a,
 {
     iter.filter(|n| n.is_zero()).count()
 }
@@ -125,6 +382,26 @@ impl<N> Arcs<N>
 where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
 {
+    /**
+     * Choose the most compact representation for the given arcs.
+     * In undirect graphs the arc (i, j) is equivalent to the arc (j, i),
+     * so only the one with i <= j is stored. If all weights are zero
+     * the arcs are stored as [Arcs::Simple], otherwise as [Arcs::Weighted].
+     */
+    pub fn new<Ni>(ni: Ni, gtype: GraphType) -> Self
+    where
+        Ni: Iterator<Item = (usize, usize, N)>,
+    {
+        let arcs: Vec<_> = ni
+            .filter(|(i, j, _)| gtype == GraphType::Direct || i <= j)
+            .collect();
+        if arcs.iter().all(|(_, _, w)| w.is_zero()) {
+            Self::new_simple(arcs.into_iter())
+        } else {
+            Self::Weighted(arcs)
+        }
+    }
+
     pub fn new_simple<Ni>(ni: Ni) -> Self
     where
         Ni: Iterator<Item = (usize, usize, N)>,
@@ -206,15 +483,84 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_arcs_constructor() {
+        let arcs = vec![(0, 1, 1), (1, 0, 1), (1, 2, 2), (2, 1, 2)];
+        let direct = Arcs::new(arcs.clone().into_iter(), GraphType::Direct);
+        assert!(matches!(direct, Arcs::Weighted(w) if w == arcs));
+
+        let undirect = Arcs::new(arcs.into_iter(), GraphType::Undirect);
+        assert!(matches!(undirect, Arcs::Weighted(w) if w == vec![(0, 1, 1), (1, 2, 2)]));
+
+        let zeros = vec![(0, 1, 0), (1, 0, 0), (2, 2, 0)];
+        let simple = Arcs::new(zeros.into_iter(), GraphType::Undirect);
+        assert!(matches!(simple, Arcs::Simple(s) if s == vec![(0, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn test_validate() {
+        let nodes = Nodes::new(vec![1, 2, 3]);
+        let arcs = Arcs::new_weighted([(0, 1, 1), (2, 3, 1)].into_iter());
+        let graph = MathGraph::new(nodes, arcs, GraphType::Direct);
+        let expect = InvalidGraph::ArcOutOfRange {
+            src: 2,
+            dst: 3,
+            node_count: 3,
+        };
+        assert_eq!(graph.validate(), Err(expect));
+
+        let nodes = Nodes::Compact(CompactNodes::new(2, vec![(0, 1), (5, 1)]));
+        let arcs = Arcs::<i32>::Simple(vec![(0, 1)]);
+        let graph = MathGraph::new(nodes, arcs, GraphType::Undirect);
+        let expect = InvalidGraph::NodeOutOfRange {
+            node: 5,
+            node_count: 2,
+        };
+        assert_eq!(graph.validate(), Err(expect));
+
+        let nodes = Nodes::new(vec![0, 0]);
+        let arcs = Arcs::new(vec![(0, 1, 0), (1, 0, 0)].into_iter(), GraphType::Undirect);
+        let graph = MathGraph::new(nodes, arcs, GraphType::Undirect);
+        assert_eq!(graph.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_header() {
+        let nodes = Nodes::new(vec![0, 0]);
+        let arcs = Arcs::<i32>::Simple(vec![(0, 1)]);
+        let graph = MathGraph::new(nodes, arcs, GraphType::Direct);
+        assert!(graph.header().is_none());
+        assert_eq!(graph.version(), 0);
+
+        let graph = graph
+            .with_representation(Representation::AdjList)
+            .with_name("pair")
+            .with_attribute("source", "test");
+        assert_eq!(graph.version(), FORMAT_VERSION);
+        assert_eq!(graph.representation(), Some(Representation::AdjList));
+        assert_eq!(graph.name(), Some("pair"));
+        assert_eq!(graph.attribute("source"), Some("test"));
+        assert_eq!(graph.attribute("missing"), None);
+        assert_eq!(graph.validate(), Ok(()));
+
+        let mut header = Header::new();
+        header.version = FORMAT_VERSION + 1;
+        let graph = graph.with_header(header);
+        let expect = InvalidGraph::UnsupportedVersion {
+            version: FORMAT_VERSION + 1,
+        };
+        assert_eq!(graph.validate(), Err(expect));
+    }
+
     #[test]
     fn test_count_zeros() {
-        let zeros = vec![0; 10];
+        let zeros = [0; 10];
         assert_eq!(count_zeros(zeros.iter()), 10);
 
-        let ones = vec![1; 10];
+        let ones = [1; 10];
         assert_eq!(count_zeros(ones.iter()), 0);
 
-        let mixed = vec![0, 1, 0, 1, 0];
+        let mixed = [0, 1, 0, 1, 0];
         assert_eq!(count_zeros(mixed.iter()), 3);
     }
 }
@@ -3,7 +3,8 @@
  */
 
 use super::math_graph;
-use super::path_cost::ArcCost;
+use super::path_cost::{ArcCost, HasArc};
+use super::set_ops::{self, SetOps};
 use super::update_nodes;
 use super::visitor;
 use super::{GetGraphType, Graph, GraphType};
@@ -99,6 +100,13 @@ where
             }
         })
     }
+
+    /**
+     * Return true if the graph contains arc (*src*, *dst*).
+     */
+    pub fn has_arc(&self, src: usize, dst: usize) -> bool {
+        self.adj_mat[(src, dst)]
+    }
 }
 
 impl<N> GetGraphType for MatrixGraph<N>
@@ -195,6 +203,15 @@ where
     }
 }
 
+impl<N> visitor::SuccessorVisitor<N> for &MatrixGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn successor_visitor<F: FnMut(usize, N)>(&self, node: usize, mut f: F) {
+        self.successor_iterator(node).for_each(|(_, j, w)| f(j, w))
+    }
+}
+
 impl<N> From<math_graph::MathGraph<N>> for MatrixGraph<N>
 where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
@@ -249,9 +266,83 @@ where
     N: num_traits::Num + Default + Clone + Copy + Serialize,
 {
     fn from(mat: MatrixGraph<N>) -> math_graph::MathGraph<N> {
-        let arcs = math_graph::Arcs::new_weighted(mat.arc_iterator());
+        let arcs = math_graph::Arcs::new(mat.arc_iterator(), mat.gtype);
         let nodes = math_graph::Nodes::new(mat.nodes);
         Self::new(nodes, arcs, mat.gtype)
+            .with_representation(math_graph::Representation::MatrixGraph)
+    }
+}
+
+impl<N> SetOps<N> for MatrixGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn union<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N,
+    {
+        self.combine(other, |a, b| set_ops::union_weight(a, b, &merge))
+    }
+
+    fn intersection<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N,
+    {
+        self.combine(other, |a, b| set_ops::intersection_weight(a, b, &merge))
+    }
+
+    fn difference(&self, other: &Self) -> Self {
+        self.combine(other, set_ops::difference_weight)
+    }
+
+    fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine(other, set_ops::symmetric_difference_weight)
+    }
+
+    fn complement(&self) -> Self {
+        let mut graph = Self::new(self.nodes.len(), self.gtype);
+        graph.nodes = self.nodes.clone();
+        Zip::indexed(&mut graph.adj_mat)
+            .and(&self.adj_mat)
+            .for_each(|(i, j), adj, a| *adj = !*a && i != j);
+        graph.count_arcs()
+    }
+}
+
+impl<N> MatrixGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    /**
+     * Build a graph with the nodes of *self* and, for each arc in either
+     * graph, the arc weighted by *f* when it returns ```Some```.
+     */
+    fn combine<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(Option<N>, Option<N>) -> Option<N>,
+    {
+        let node_count = self.nodes.len();
+        set_ops::check_compatible((node_count, self.gtype), (other.nodes.len(), other.gtype));
+        let mut graph = Self::new(node_count, self.gtype);
+        graph.nodes = self.nodes.clone();
+        Zip::from(&mut graph.adj_mat)
+            .and(&mut graph.weight_mat)
+            .and(&self.adj_mat)
+            .and(&self.weight_mat)
+            .and(&other.adj_mat)
+            .and(&other.weight_mat)
+            .for_each(|adj, w, a1, w1, a2, w2| {
+                if let Some(new_w) = f(a1.then_some(*w1), a2.then_some(*w2)) {
+                    *adj = true;
+                    *w = new_w;
+                }
+            });
+        graph.count_arcs()
+    }
+
+    fn count_arcs(mut self) -> Self {
+        self.arc_count = self.adj_mat.iter().filter(|a| **a).count();
+        self
     }
 }
 
@@ -264,11 +355,21 @@ where
     }
 }
 
+impl<N> HasArc for &MatrixGraph<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    fn has_arc(&self, src: usize, dst: usize) -> bool {
+        MatrixGraph::has_arc(self, src, dst)
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
     use super::super::tests;
+    use super::super::geometric::Metric;
     use crate::visitor::GraphVisitor;
 
     #[test]
@@ -342,7 +443,7 @@ mod test {
     #[test]
     fn test_node_visitor() {
         let mut graph = make_graph();
-        graph.update_all_nodes_weight(|i, _| (i as f64));
+        graph.update_all_nodes_weight(|i, _| i as f64);
         let mut visit_list: Vec<(usize, f64)> = vec![];
         (&graph).node_visitor(|i, n| visit_list.push((i, n)));
         assert_eq!(vec![(0, 0.0), (1, 1.0), (2, 2.0), (3, 3.0)], visit_list);
@@ -376,7 +477,7 @@ mod test {
 
         let points = [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)];
 
-        graph.update_all_arcs_weight(|i, j, _| tests::euclid_distance(&points[i], &points[j]));
+        graph.update_all_arcs_weight(|i, j, _| Metric::Euclidean.distance(&points[i], &points[j]));
 
         for (i, j, w) in graph.arc_iterator() {
             match (i, j) {
@@ -0,0 +1,317 @@
+/*!
+ * Read and write graphs as [Matrix Market](https://math.nist.gov/MatrixMarket/formats.html)
+ * `coordinate` files, the format used by the
+ * [SuiteSparse](https://sparse.tamu.edu/) matrix collection.
+ * Each entry (*i*, *j*) of the matrix is an arc from node *i* to node *j*.
+ * `general` matrices are mapped to direct graphs and `symmetric` matrices
+ * to undirect ones. Matrix Market numbers rows and columns starting from 1
+ * while this crate starts from 0: node indexes are converted while reading
+ * and writing.
+ */
+
+use super::read_error::ReadError;
+use super::visitor;
+use super::{GetGraphType, Graph, GraphType};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/**
+ * Read a graph from a Matrix Market `coordinate` file. Supported fields
+ * are `real`, `integer` and `pattern`: entries of a `pattern` file
+ * are inserted using [add_new_default_arc](Graph::add_new_default_arc).
+ * Supported symmetries are `general` and `symmetric`.
+ *
+ * ```
+ * use simplegraph::{GetGraphType, GraphType, MatrixGraph};
+ * use simplegraph::matrix_market::read_matrix_market;
+ *
+ * let source = "%%MatrixMarket matrix coordinate pattern symmetric\n3 3 2\n2 1\n3 2\n";
+ * let graph: MatrixGraph<f64> = read_matrix_market(source.as_bytes()).unwrap();
+ * assert_eq!(graph.graph_type(), GraphType::Undirect);
+ * assert_eq!(graph.arc_iterator().count(), 4);
+ * ```
+ */
+pub fn read_matrix_market<R, G, N>(reader: R) -> Result<G, ReadError>
+where
+    R: io::BufRead,
+    G: Graph<N>,
+    N: num_traits::Num + FromStr,
+{
+    let mut lines = reader.lines().enumerate();
+    let (line_num, banner) = match lines.next() {
+        Some((index, line)) => (index + 1, line?),
+        None => return Err(ReadError::syntax(1, "missing Matrix Market banner")),
+    };
+    let (pattern, gtype) = parse_banner(&banner, line_num)?;
+
+    let mut graph: Option<(G, usize, usize)> = None;
+    let mut found_entries = 0;
+    let mut last_line = line_num;
+    for (index, line) in lines {
+        let line = line?;
+        let line_num = index + 1;
+        last_line = line_num;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        match &mut graph {
+            None => {
+                let rows: usize = parse_token(&mut tokens, line_num, "row count")?;
+                let cols: usize = parse_token(&mut tokens, line_num, "column count")?;
+                let entries = parse_token(&mut tokens, line_num, "entry count")?;
+                check_end(&mut tokens, line_num)?;
+                if rows != cols {
+                    let message = format!("expected a square matrix, found {rows}x{cols}");
+                    return Err(ReadError::syntax(line_num, message));
+                }
+                graph = Some((G::new(rows, gtype), rows, entries));
+            }
+            Some((g, nodes, _)) => {
+                let i = parse_index(&mut tokens, line_num, *nodes)?;
+                let j = parse_index(&mut tokens, line_num, *nodes)?;
+                if pattern {
+                    check_end(&mut tokens, line_num)?;
+                    g.add_new_default_arc(i, j);
+                } else {
+                    let w = parse_token(&mut tokens, line_num, "value")?;
+                    check_end(&mut tokens, line_num)?;
+                    g.add_new_arc(i, j, w);
+                }
+                found_entries += 1;
+            }
+        }
+    }
+
+    let (graph, _, entries) =
+        graph.ok_or_else(|| ReadError::syntax(last_line, "missing size line"))?;
+    if entries != found_entries {
+        let message = format!("expected {entries} entries, found {found_entries}");
+        return Err(ReadError::syntax(last_line, message));
+    }
+    Ok(graph)
+}
+
+/**
+ * Write the given [GraphVisitor](visitor::GraphVisitor) into *writer*
+ * as a Matrix Market `coordinate real` file. Direct graphs are written
+ * as `general` matrices, undirect graphs as `symmetric` matrices storing
+ * only the lower triangle.
+ */
+pub fn write_matrix_market<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy + fmt::Display,
+{
+    write_entries(writer, g, "real", |writer, i, j, w| {
+        writeln!(writer, "{} {} {}", i + 1, j + 1, w)
+    })
+}
+
+/**
+ * Like [write_matrix_market], write only the graph's structure as
+ * a Matrix Market `coordinate pattern` file, ignoring arc weights.
+ */
+pub fn write_matrix_market_pattern<W, G, N>(writer: &mut W, g: G) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy,
+{
+    write_entries(writer, g, "pattern", |writer, i, j, _| {
+        writeln!(writer, "{} {}", i + 1, j + 1)
+    })
+}
+
+fn write_entries<W, G, N, F>(writer: &mut W, g: G, field: &str, mut f: F) -> io::Result<()>
+where
+    W: io::Write,
+    G: visitor::GraphVisitor<N> + GetGraphType + Copy,
+    N: num_traits::Num + Copy,
+    F: FnMut(&mut W, usize, usize, N) -> io::Result<()>,
+{
+    let (symmetry, keep): (_, fn(usize, usize) -> bool) = match g.graph_type() {
+        GraphType::Direct => ("general", |_, _| true),
+        GraphType::Undirect => ("symmetric", |i, j| i >= j),
+    };
+    let mut entries = 0;
+    g.arc_visitor(|i, j, _| {
+        if keep(i, j) {
+            entries += 1;
+        }
+    });
+
+    writeln!(
+        writer,
+        "%%MatrixMarket matrix coordinate {field} {symmetry}"
+    )?;
+    let nodes = g.node_count();
+    writeln!(writer, "{nodes} {nodes} {entries}")?;
+    let mut status = Ok(());
+    g.arc_visitor(|i, j, w| {
+        if status.is_ok() && keep(i, j) {
+            status = f(writer, i, j, w);
+        }
+    });
+    status
+}
+
+fn parse_banner(banner: &str, line: usize) -> Result<(bool, GraphType), ReadError> {
+    let tokens: Vec<_> = banner
+        .split_whitespace()
+        .map(|t| t.to_ascii_lowercase())
+        .collect();
+    let tokens: Vec<_> = tokens.iter().map(String::as_str).collect();
+    match tokens.as_slice() {
+        ["%%matrixmarket", "matrix", "coordinate", field, symmetry] => {
+            let pattern = match *field {
+                "real" | "integer" => false,
+                "pattern" => true,
+                other => {
+                    let message = format!("unsupported field \"{other}\"");
+                    return Err(ReadError::syntax(line, message));
+                }
+            };
+            let gtype = match *symmetry {
+                "general" => GraphType::Direct,
+                "symmetric" => GraphType::Undirect,
+                other => {
+                    let message = format!("unsupported symmetry \"{other}\"");
+                    return Err(ReadError::syntax(line, message));
+                }
+            };
+            Ok((pattern, gtype))
+        }
+        ["%%matrixmarket", "matrix", format, ..] if *format != "coordinate" => {
+            let message = format!("unsupported format \"{format}\"");
+            Err(ReadError::syntax(line, message))
+        }
+        _ => Err(ReadError::syntax(line, "invalid Matrix Market banner")),
+    }
+}
+
+fn parse_token<'a, I, T>(tokens: &mut I, line: usize, what: &str) -> Result<T, ReadError>
+where
+    I: Iterator<Item = &'a str>,
+    T: FromStr,
+{
+    let token = tokens
+        .next()
+        .ok_or_else(|| ReadError::syntax(line, format!("missing {what}")))?;
+    token
+        .parse()
+        .map_err(|_| ReadError::syntax(line, format!("invalid {what} \"{token}\"")))
+}
+
+fn parse_index<'a, I>(tokens: &mut I, line: usize, nodes: usize) -> Result<usize, ReadError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let index: usize = parse_token(tokens, line, "index")?;
+    if index == 0 || index > nodes {
+        let message = format!("index {index} out of range 1..={nodes}");
+        Err(ReadError::syntax(line, message))
+    } else {
+        Ok(index - 1)
+    }
+}
+
+fn check_end<'a, I>(tokens: &mut I, line: usize) -> Result<(), ReadError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match tokens.next() {
+        Some(token) => Err(ReadError::syntax(
+            line,
+            format!("unexpected token \"{token}\""),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::{AdjList, MatrixGraph};
+
+    #[test]
+    fn test_read_general() {
+        let source = "%%MatrixMarket matrix coordinate real general
+% a comment
+3 3 3
+1 2 1.5
+3 1 -2
+2 2 4e1
+";
+        let graph: MatrixGraph<f64> = read_matrix_market(source.as_bytes()).unwrap();
+        assert_eq!(graph.graph_type(), GraphType::Direct);
+        let arcs: Vec<_> = graph.arc_iterator().collect();
+        assert_eq!(arcs, vec![(0, 1, 1.5), (1, 1, 40.0), (2, 0, -2.0)]);
+    }
+
+    #[test]
+    fn test_symmetric_round_trip() {
+        let mut graph = MatrixGraph::new_undirect(3);
+        graph.add_new_arc(0, 1, 7);
+        graph.add_new_arc(2, 1, 3);
+
+        let mut buff = vec![];
+        write_matrix_market(&mut buff, &graph).unwrap();
+        let expect = "%%MatrixMarket matrix coordinate real symmetric\n3 3 2\n2 1 7\n3 2 3\n";
+        assert_eq!(String::from_utf8(buff.clone()).unwrap(), expect);
+
+        let new_graph: MatrixGraph<i32> = read_matrix_market(buff.as_slice()).unwrap();
+        let arcs: Vec<_> = new_graph.arc_iterator().collect();
+        let expect: Vec<_> = graph.arc_iterator().collect();
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_pattern_round_trip() {
+        let mut graph = AdjList::new_direct(3);
+        graph.add_new_arc(0, 2, 7);
+        graph.add_new_arc(2, 1, 3);
+
+        let mut buff = vec![];
+        write_matrix_market_pattern(&mut buff, &graph).unwrap();
+        let expect = "%%MatrixMarket matrix coordinate pattern general\n3 3 2\n1 3\n3 2\n";
+        assert_eq!(String::from_utf8(buff.clone()).unwrap(), expect);
+
+        let new_graph: AdjList<i32> = read_matrix_market(buff.as_slice()).unwrap();
+        let arcs: Vec<_> = new_graph.arc_iterator().collect();
+        assert_eq!(arcs, vec![(0, 2, 0), (2, 1, 0)]);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let source = "%%MatrixMarket matrix array real general\n2 2\n1\n2\n3\n4\n";
+        let err = read_matrix_market::<_, AdjList<f64>, f64>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: unsupported format \"array\"");
+
+        let source = "%%MatrixMarket matrix coordinate real hermitian\n2 2 0\n";
+        let err = read_matrix_market::<_, AdjList<f64>, f64>(source.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: unsupported symmetry \"hermitian\""
+        );
+
+        let source = "%%MatrixMarket matrix coordinate real general\n2 3 0\n";
+        let err = read_matrix_market::<_, AdjList<f64>, f64>(source.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: expected a square matrix, found 2x3"
+        );
+
+        let source = "%%MatrixMarket matrix coordinate real general\n2 2 1\n%\n1 3 1.0\n";
+        let err = read_matrix_market::<_, AdjList<f64>, f64>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 4: index 3 out of range 1..=2");
+
+        let source = "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n";
+        let err = read_matrix_market::<_, AdjList<f64>, f64>(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: expected 2 entries, found 1");
+    }
+}
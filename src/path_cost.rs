@@ -2,6 +2,11 @@
  * Compute the cost of all sub path.
  */
 
+use super::{GetGraphType, GraphType};
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
 use std::ops::Range;
 use std::slice::Iter;
 
@@ -19,14 +24,114 @@ where
     fn cost(&self, src: usize, dst: usize) -> N;
 }
 
+/**
+ * Implementations of this trait allows to
+ * query the existence of an arc.
+ */
+pub trait HasArc {
+    /**
+     * Return true if the graph contains arc (*src*, *dst*).
+     */
+    fn has_arc(&self, src: usize, dst: usize) -> bool;
+}
+
+/**
+ * Error returned by [path_cost] when two consecutive
+ * nodes in the path are not connected by an arc.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingArc {
+    pub src: usize,
+    pub dst: usize,
+}
+
+impl fmt::Display for MissingArc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing arc ({}, {})", self.src, self.dst)
+    }
+}
+
+impl Error for MissingArc {}
+
+/**
+ * Return the cost of the whole *path*, the sum of the weights of the
+ * arcs between consecutive nodes, or the first [MissingArc].
+ * Paths with less than two nodes cost
+ * [num_traits::Num::zero()](https://docs.rs/num/latest/num/traits/trait.Zero.html).
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::path_cost::{path_cost, MissingArc};
+ *
+ * let mut graph = AdjList::<u32>::new_direct(3);
+ * graph.add_new_arc(0, 1, 3);
+ * graph.add_new_arc(1, 2, 4);
+ *
+ * assert_eq!(path_cost(&graph, &[0, 1, 2]), Ok(7));
+ * assert_eq!(path_cost(&graph, &[0, 2]), Err(MissingArc { src: 0, dst: 2 }));
+ * ```
+ */
+pub fn path_cost<G, N>(graph: G, path: &[usize]) -> Result<N, MissingArc>
+where
+    G: ArcCost<N> + HasArc,
+    N: num_traits::Num,
+{
+    SuccessorIterator::new(path.iter()).try_fold(N::zero(), |cost, (src, dst)| {
+        if graph.has_arc(src, dst) {
+            Ok(cost + graph.cost(src, dst))
+        } else {
+            Err(MissingArc { src, dst })
+        }
+    })
+}
+
+/**
+ * Return true if each pair of consecutive nodes
+ * in *path* is connected by an arc.
+ */
+pub fn is_valid_path<G: HasArc>(graph: G, path: &[usize]) -> bool {
+    SuccessorIterator::new(path.iter()).all(|(src, dst)| graph.has_arc(src, dst))
+}
+
+/**
+ * Return true if *path* is valid and does not visit any node twice.
+ */
+pub fn is_simple_path<G: HasArc>(graph: G, path: &[usize]) -> bool {
+    let mut visited = HashSet::with_capacity(path.len());
+    path.iter().all(|node| visited.insert(*node)) && is_valid_path(graph, path)
+}
+
+/**
+ * Return true if *path* is a cycle visiting each of the
+ * *node_count* nodes in the graph exactly once. The cycle can be given
+ * either as a closed tour, as for [TourCost], or with the first node
+ * repeated at the end.
+ */
+pub fn is_hamiltonian_cycle<G: HasArc>(graph: G, path: &[usize], node_count: usize) -> bool {
+    let tour = match path {
+        [first, .., last] if path.len() == node_count + 1 && first == last => &path[..node_count],
+        _ => path,
+    };
+    match tour {
+        [] => node_count == 0,
+        [node] => node_count == 1 && *node == 0,
+        [first, .., last] => {
+            tour.len() == node_count
+                && tour.iter().all(|node| *node < node_count)
+                && graph.has_arc(*last, *first)
+                && is_simple_path(graph, tour)
+        }
+    }
+}
+
 /**
  * Implement an iterator over the
  * cost (length)  of all the sub-path
  * of the given path on graph G.
  * Note: only the subpath in one direction
  * are yielded by the iterator, this behavior is coherent
- * with direct graphs and it is trivial to adapt the
- * result to undirected graphs.
+ * with direct graphs. See [TourCost] for closed tours
+ * and for sub paths in both directions on undirected graphs.
  *
  * ```
  * use simplegraph::MatrixGraph;
@@ -134,6 +239,236 @@ where
     }
 }
 
+/**
+ * Costs on a closed tour: the path visits the nodes in order and
+ * then returns from the last node to the first one. Prefix-cost tables are
+ * computed once, in O(*n*), then the cost of any sub path and the cost
+ * change of 2-opt and or-opt moves are answered in O(1).
+ * Positions, not node indexes, identify nodes in the tour and wrap around:
+ * the sub path from position *i* to position *j* < *i* goes through the
+ * last-to-first arc.
+ *
+ * Sub paths can be traversed backward: on undirect graphs this is equal
+ * to the forward cost, on direct graphs it is the cost of the reversed arcs,
+ * whose table is built on first use. Only the backward costs, 2-opt moves
+ * and reversed or-opt moves need the reversed arcs: on direct graphs
+ * they panic unless [TourCost::is_reversible].
+ *
+ * ```
+ * use simplegraph::MatrixGraph;
+ * use simplegraph::Graph;
+ * use simplegraph::path_cost::TourCost;
+ *
+ * let mut graph = MatrixGraph::<i32>::new_undirect(4);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 2, 5);
+ * graph.add_new_arc(2, 3, 1);
+ * graph.add_new_arc(3, 0, 5);
+ * graph.add_new_arc(0, 2, 1);
+ * graph.add_new_arc(1, 3, 1);
+ *
+ * let tour = TourCost::new(&graph, &[0, 1, 2, 3]);
+ * assert_eq!(tour.length(), 12);
+ * assert_eq!(tour.sub_path_cost(3, 1), 6);
+ * // tour [0, 2, 1, 3] costs 1 + 5 + 1 + 5
+ * assert_eq!(tour.two_opt_delta(0, 2), 0);
+ * // tour [0, 1, 3, 2] costs 1 + 1 + 1 + 1
+ * assert_eq!(tour.two_opt_delta(1, 3), -8);
+ * ```
+ */
+pub struct TourCost<'a, G, N>
+where
+    G: ArcCost<N>,
+    N: num_traits::Num,
+{
+    graph: G,
+    tour: &'a [usize],
+    gtype: GraphType,
+    forward: Vec<N>,
+    backward: OnceCell<Vec<N>>,
+}
+
+impl<'a, G, N> TourCost<'a, G, N>
+where
+    G: ArcCost<N> + GetGraphType + HasArc,
+    N: num_traits::Num + Copy,
+{
+    /**
+     * Initialize a TourCost for graph *g* with the given closed *tour*.
+     * Each entry in *tour* is the index of a node in *g*, the first node
+     * should not be repeated at the end. Tours with less than two
+     * nodes have no arcs.
+     */
+    pub fn new(g: G, tour: &'a [usize]) -> Self {
+        let gtype = g.graph_type();
+        let arcs = if tour.len() > 1 { tour.len() } else { 0 };
+        let mut forward = Vec::with_capacity(arcs + 1);
+        forward.push(N::zero());
+        for k in 0..arcs {
+            let (src, dst) = (tour[k], tour[(k + 1) % arcs]);
+            forward.push(forward[k] + g.cost(src, dst));
+        }
+        Self {
+            graph: g,
+            tour,
+            gtype,
+            forward,
+            backward: OnceCell::new(),
+        }
+    }
+
+    /**
+     * Return true if the tour can be traversed backward: always on
+     * undirect graphs, when all the reversed arcs exist on direct ones.
+     */
+    pub fn is_reversible(&self) -> bool {
+        self.gtype == GraphType::Undirect
+            || self
+                .reversed_arcs()
+                .all(|(src, dst)| self.graph.has_arc(src, dst))
+    }
+
+    /**
+     * Reversed tour arcs, in tour order.
+     */
+    fn reversed_arcs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let arcs = self.forward.len() - 1;
+        (0..arcs).map(move |k| (self.tour[(k + 1) % arcs], self.tour[k]))
+    }
+
+    fn backward(&self) -> &[N] {
+        if self.gtype == GraphType::Undirect {
+            return &self.forward;
+        }
+        self.backward.get_or_init(|| {
+            let mut backward = Vec::with_capacity(self.forward.len());
+            backward.push(N::zero());
+            for (k, (src, dst)) in self.reversed_arcs().enumerate() {
+                assert!(
+                    self.graph.has_arc(src, dst),
+                    "missing reversed arc ({src}, {dst})"
+                );
+                backward.push(backward[k] + self.graph.cost(src, dst));
+            }
+            backward
+        })
+    }
+
+    /**
+     * Return the cost of the whole closed tour.
+     */
+    pub fn length(&self) -> N {
+        *self.forward.last().unwrap()
+    }
+
+    /**
+     * Return the cost of the sub path going forward
+     * from position *i* to position *j*.
+     */
+    pub fn sub_path_cost(&self, i: usize, j: usize) -> N {
+        Self::prefix_cost(&self.forward, i, j)
+    }
+
+    /**
+     * Return the cost of the sub path from position *j*
+     * back to position *i*, traversing the tour backward.
+     * Panics if the tour is not [reversible](TourCost::is_reversible).
+     */
+    pub fn reverse_sub_path_cost(&self, i: usize, j: usize) -> N {
+        Self::prefix_cost(self.backward(), i, j)
+    }
+
+    fn prefix_cost(table: &[N], i: usize, j: usize) -> N {
+        if i <= j {
+            table[j] - table[i]
+        } else {
+            let last = table.len() - 1;
+            table[last] - table[i] + table[j]
+        }
+    }
+
+    /**
+     * Return an iterator over the costs of all the sub paths in the tour,
+     * as (source node, destination node, cost) tuples. Each sub path
+     * starts at some position and goes forward, wrapping around,
+     * through one to *n* - 1 arcs. On undirect graphs each sub path is
+     * also yielded backward, right after the forward one.
+     */
+    pub fn sub_path_costs(&'_ self) -> impl Iterator<Item = (usize, usize, N)> + '_ {
+        let n = self.tour.len();
+        let both = self.gtype == GraphType::Undirect;
+        (0..n).flat_map(move |i| {
+            (1..n).flat_map(move |step| {
+                let j = (i + step) % n;
+                let (src, dst) = (self.tour[i], self.tour[j]);
+                let forward = (src, dst, self.sub_path_cost(i, j));
+                let backward = both.then(|| (dst, src, self.reverse_sub_path_cost(i, j)));
+                std::iter::once(forward).chain(backward)
+            })
+        })
+    }
+
+    /**
+     * Return the cost change of the 2-opt move removing the arcs leaving
+     * positions *i* and *j*, with *i* < *j*, and reversing the nodes
+     * between them: the tour
+     * *a*, *t\[i\]*, *t\[i + 1\]*, ..., *t\[j\]*, *t\[j + 1\]*, *b*
+     * becomes
+     * *a*, *t\[i\]*, *t\[j\]*, ..., *t\[i + 1\]*, *t\[j + 1\]*, *b*.
+     * A negative value means the move shortens the tour. On direct
+     * graphs the tour must be [reversible](TourCost::is_reversible),
+     * otherwise this panics.
+     */
+    pub fn two_opt_delta(&self, i: usize, j: usize) -> N {
+        debug_assert!(i < j && j < self.tour.len(), "invalid 2-opt move");
+        let n = self.tour.len();
+        let (a, b) = (self.tour[i], self.tour[i + 1]);
+        let (c, d) = (self.tour[j], self.tour[(j + 1) % n]);
+        let removed = self.graph.cost(a, b) + self.graph.cost(c, d);
+        let added = self.graph.cost(a, c) + self.graph.cost(b, d);
+        added - removed + self.reversal_delta(i + 1, j)
+    }
+
+    /**
+     * Return the cost change of the or-opt move taking the *len* nodes
+     * starting at position *start* and inserting them, possibly *reversed*,
+     * between positions *target* and *target* + 1. *target* must be
+     * outside the moved segment and must not be the position
+     * right before it. A negative value means the move shortens the tour.
+     * For *reversed* moves on direct graphs the tour must be
+     * [reversible](TourCost::is_reversible), otherwise this panics.
+     */
+    pub fn or_opt_delta(&self, start: usize, len: usize, target: usize, reversed: bool) -> N {
+        let n = self.tour.len();
+        debug_assert!(len > 0 && len + 2 <= n, "invalid or-opt segment");
+        let end = (start + len - 1) % n;
+        debug_assert!(
+            (target + n - start) % n >= len && (target + 1) % n != start,
+            "invalid or-opt target"
+        );
+        let p = self.tour[(start + n - 1) % n];
+        let q = self.tour[(end + 1) % n];
+        let (s, e) = (self.tour[start], self.tour[end]);
+        let (a, b) = (self.tour[target], self.tour[(target + 1) % n]);
+
+        let removed = self.graph.cost(p, s) + self.graph.cost(e, q) + self.graph.cost(a, b);
+        let closed = self.graph.cost(p, q);
+        if reversed {
+            let added = closed + self.graph.cost(a, e) + self.graph.cost(s, b);
+            added - removed + self.reversal_delta(start, end)
+        } else {
+            closed + self.graph.cost(a, s) + self.graph.cost(e, b) - removed
+        }
+    }
+
+    fn reversal_delta(&self, i: usize, j: usize) -> N {
+        match self.gtype {
+            GraphType::Direct => self.reverse_sub_path_cost(i, j) - self.sub_path_cost(i, j),
+            GraphType::Undirect => N::zero(),
+        }
+    }
+}
+
 struct SuccessorIterator<'a> {
     iter: Iter<'a, usize>,
     prev: Option<usize>,
@@ -164,8 +499,29 @@ impl<'a> Iterator for SuccessorIterator<'a> {
 mod test {
 
     use super::*;
+    use crate::views::{InducedSubgraph, Reversed};
     use crate::Graph;
-    use crate::MatrixGraph;
+    use crate::{AdjList, MatrixGraph};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn tour_length(graph: &MatrixGraph<i64>, tour: &[usize]) -> i64 {
+        let n = tour.len();
+        (0..n).map(|k| graph.cost(tour[k], tour[(k + 1) % n])).sum()
+    }
+
+    fn random_complete(n: usize, gtype: GraphType) -> MatrixGraph<i64> {
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut graph = MatrixGraph::new(n, gtype);
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    graph.add_new_arc(i, j, rng.gen_range(1..100));
+                }
+            }
+        }
+        graph
+    }
 
     #[test]
     fn test_path_cost_iterator() {
@@ -231,4 +587,178 @@ mod test {
         assert_eq!(iter.next(), Some((7, 8)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_tour_cost() {
+        let mut graph = MatrixGraph::<i64>::new_direct(3);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 0, 4);
+        let tour = TourCost::new(&graph, &[0, 1, 2]);
+        assert_eq!(tour.length(), 7);
+        assert_eq!(tour.sub_path_cost(2, 1), 5);
+        let costs: Vec<_> = tour.sub_path_costs().collect();
+        let expect = vec![
+            (0, 1, 1),
+            (0, 2, 3),
+            (1, 2, 2),
+            (1, 0, 6),
+            (2, 0, 4),
+            (2, 1, 5),
+        ];
+        assert_eq!(costs, expect);
+
+        let graph = random_complete(5, GraphType::Undirect);
+        let order = [3, 1, 4, 0, 2];
+        let tour = TourCost::new(&graph, &order);
+        let costs: Vec<_> = tour.sub_path_costs().collect();
+        assert_eq!(costs.len(), 2 * 5 * 4);
+        for pair in costs.chunks(2) {
+            let (src, dst, cost) = pair[0];
+            assert_eq!(pair[1], (dst, src, cost));
+        }
+
+        assert_eq!(TourCost::new(&graph, &[2]).length(), 0);
+    }
+
+    #[test]
+    fn test_sparse_direct_tour() {
+        let mut graph = AdjList::<i64>::new_direct(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 3);
+        graph.add_new_arc(3, 0, 4);
+        graph.add_new_arc(2, 1, 5);
+        let tour = TourCost::new(&graph, &[0, 1, 2, 3]);
+        assert_eq!(tour.length(), 10);
+        assert_eq!(tour.sub_path_cost(3, 2), 7);
+        assert!(!tour.is_reversible());
+        assert_eq!(tour.sub_path_costs().count(), 12);
+
+        let mut graph = MatrixGraph::<i64>::new_direct(3);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 0, 4);
+        graph.add_new_arc(1, 0, 3);
+        graph.add_new_arc(2, 1, 5);
+        assert!(!TourCost::new(&graph, &[0, 1, 2]).is_reversible());
+        graph.add_new_arc(0, 2, 6);
+        let tour = TourCost::new(&graph, &[0, 1, 2]);
+        assert!(tour.is_reversible());
+        assert_eq!(tour.reverse_sub_path_cost(0, 2), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "missing reversed arc (1, 0)")]
+    fn test_sparse_direct_two_opt() {
+        let mut graph = MatrixGraph::<i64>::new_direct(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 3);
+        graph.add_new_arc(3, 0, 4);
+        graph.add_new_arc(0, 2, 1);
+        graph.add_new_arc(1, 3, 1);
+        graph.add_new_arc(2, 1, 1);
+        TourCost::new(&graph, &[0, 1, 2, 3]).two_opt_delta(0, 2);
+    }
+
+    #[test]
+    fn test_two_opt_delta() {
+        for gtype in [GraphType::Direct, GraphType::Undirect] {
+            let graph = random_complete(8, gtype);
+            let order = [5, 2, 7, 0, 3, 6, 1, 4];
+            let tour = TourCost::new(&graph, &order);
+            assert_eq!(tour.length(), tour_length(&graph, &order));
+            for i in 0..8 {
+                for j in (i + 1)..8 {
+                    let mut moved = order.to_vec();
+                    moved[(i + 1)..=j].reverse();
+                    let expect = tour_length(&graph, &moved) - tour.length();
+                    assert_eq!(tour.two_opt_delta(i, j), expect, "move ({i}, {j})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_or_opt_delta() {
+        let n = 8;
+        for gtype in [GraphType::Direct, GraphType::Undirect] {
+            let graph = random_complete(n, gtype);
+            let order = [5, 2, 7, 0, 3, 6, 1, 4];
+            let tour = TourCost::new(&graph, &order);
+            for start in 0..n {
+                for len in 1..=3 {
+                    for target in 0..n {
+                        let offset = (target + n - start) % n;
+                        if offset < len || (target + 1) % n == start {
+                            continue;
+                        }
+                        for reversed in [false, true] {
+                            // rotate so that the segment starts the tour
+                            let mut segment: Vec<_> =
+                                (0..len).map(|k| order[(start + k) % n]).collect();
+                            if reversed {
+                                segment.reverse();
+                            }
+                            let mut moved: Vec<_> =
+                                (len..=offset).map(|k| order[(start + k) % n]).collect();
+                            moved.extend(segment);
+                            moved.extend(((offset + 1)..n).map(|k| order[(start + k) % n]));
+                            let expect = tour_length(&graph, &moved) - tour.length();
+                            let delta = tour.or_opt_delta(start, len, target, reversed);
+                            assert_eq!(
+                                delta, expect,
+                                "move ({start}, {len}, {target}, {reversed})"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_cost() {
+        let mut graph = AdjList::<u32>::new_undirect(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 3);
+        assert_eq!(path_cost(&graph, &[3, 2, 1, 0]), Ok(6));
+        assert_eq!(path_cost(&graph, &[1, 0, 1]), Ok(2));
+        assert_eq!(path_cost(&graph, &[2]), Ok(0));
+        assert_eq!(path_cost(&graph, &[]), Ok(0));
+        let err = path_cost(&graph, &[0, 1, 3, 2]).unwrap_err();
+        assert_eq!(err, MissingArc { src: 1, dst: 3 });
+        assert_eq!(err.to_string(), "missing arc (1, 3)");
+    }
+
+    #[test]
+    fn test_path_validation() {
+        let mut graph = MatrixGraph::<u32>::new_direct(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 1);
+        graph.add_new_arc(2, 3, 1);
+        graph.add_new_arc(3, 0, 1);
+        graph.add_new_arc(2, 0, 1);
+
+        assert!(is_valid_path(&graph, &[0, 1, 2, 0, 1]));
+        assert!(!is_valid_path(&graph, &[0, 2]));
+        assert!(is_valid_path(&graph, &[3]));
+        assert!(!is_simple_path(&graph, &[0, 1, 2, 0, 1]));
+        assert!(is_simple_path(&graph, &[1, 2, 3, 0]));
+
+        assert!(is_hamiltonian_cycle(&graph, &[0, 1, 2, 3], 4));
+        assert!(is_hamiltonian_cycle(&graph, &[2, 3, 0, 1, 2], 4));
+        assert!(!is_hamiltonian_cycle(&graph, &[0, 1, 2], 4));
+        assert!(!is_hamiltonian_cycle(&graph, &[0, 1, 2, 3, 1], 4));
+        assert!(!is_hamiltonian_cycle(&graph, &[3, 2, 1, 0], 4));
+        assert!(!is_hamiltonian_cycle(&graph, &[0, 1, 2, 0, 1], 4));
+        assert!(is_hamiltonian_cycle(&graph, &[], 0));
+
+        let reversed = Reversed::new(&graph);
+        assert!(is_hamiltonian_cycle(&reversed, &[3, 2, 1, 0], 4));
+        let sub = InducedSubgraph::new(&graph, &[0, 1, 2]);
+        assert!(is_hamiltonian_cycle(&sub, &[0, 1, 2], 3));
+    }
 }
@@ -0,0 +1,429 @@
+/*!
+ * [Graph products](https://en.wikipedia.org/wiki/Graph_product) and
+ * [line graphs](https://en.wikipedia.org/wiki/Line_graph).
+ *
+ * The product of graph *A*, with *na* nodes, and graph *B*, with *nb* nodes,
+ * has *na* * *nb* nodes: node (*u*, *v*), *u* in *A* and *v* in *B*, has index
+ * *u* * *nb* + *v*, see [product_node] and [factor_nodes]. Its weight is computed
+ * by the node closure from the weights of *u* and *v*. The weight of each arc is
+ * computed by the arc closure from the weights of the arcs of *A* and *B*
+ * it comes from, ```None``` when the arc stays still on that factor.
+ * Both factors must have the same [GraphType], which is also
+ * the type of the product: the products panic otherwise.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph, GraphType, GraphVisitor, MatrixGraph};
+ * use simplegraph::families::path;
+ * use simplegraph::products::cartesian;
+ *
+ * // the cartesian product of two paths is a grid
+ * let a: AdjList<u32> = path(3, GraphType::Undirect);
+ * let b: AdjList<u32> = path(2, GraphType::Undirect);
+ * let grid: MatrixGraph<u32> = cartesian(&a, &b, |_, _| 0, |wa, wb| {
+ *     if wa.is_some() { 1 } else { 2 }
+ * });
+ * assert_eq!((&grid).node_count(), 6);
+ * assert_eq!(grid.arc_iterator().count(), 2 * 7);
+ * ```
+ */
+
+use super::visitor::GraphVisitor;
+use super::{GetGraphType, Graph, GraphType};
+use std::collections::HashMap;
+
+/**
+ * Index of node (*u*, *v*) in a product whose second factor has *nb* nodes.
+ */
+pub fn product_node(u: usize, v: usize, nb: usize) -> usize {
+    u * nb + v
+}
+
+/**
+ * Inverse of [product_node]: return the nodes (*u*, *v*)
+ * of the factors corresponding to the product node *k*.
+ */
+pub fn factor_nodes(k: usize, nb: usize) -> (usize, usize) {
+    (k / nb, k % nb)
+}
+
+/**
+ * Cartesian product: (*u*, *v*) is connected to (*u'*, *v'*) when
+ * *u* = *u'* and *v* is connected to *v'* in *B*, or when
+ * *v* = *v'* and *u* is connected to *u'* in *A*.
+ */
+pub fn cartesian<GA, GB, G, N, FN, FA>(a: GA, b: GB, node_weight: FN, arc_weight: FA) -> G
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    GB: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(Option<N>, Option<N>) -> N,
+{
+    let factors = Factors::new(&a, &b);
+    let mut arcs = vec![];
+    factors.cartesian_arcs(&mut arcs);
+    factors.build(arcs, node_weight, arc_weight)
+}
+
+/**
+ * Tensor (Kronecker) product: (*u*, *v*) is connected to (*u'*, *v'*) when
+ * *u* is connected to *u'* in *A* and *v* is connected to *v'* in *B*.
+ */
+pub fn tensor<GA, GB, G, N, FN, FA>(a: GA, b: GB, node_weight: FN, arc_weight: FA) -> G
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    GB: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(Option<N>, Option<N>) -> N,
+{
+    let factors = Factors::new(&a, &b);
+    let mut arcs = vec![];
+    factors.tensor_arcs(&mut arcs);
+    factors.build(arcs, node_weight, arc_weight)
+}
+
+/**
+ * Strong product: the union of the [cartesian] and the [tensor] products.
+ */
+pub fn strong<GA, GB, G, N, FN, FA>(a: GA, b: GB, node_weight: FN, arc_weight: FA) -> G
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    GB: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(Option<N>, Option<N>) -> N,
+{
+    let factors = Factors::new(&a, &b);
+    let mut arcs = vec![];
+    factors.cartesian_arcs(&mut arcs);
+    factors.tensor_arcs(&mut arcs);
+    factors.build(arcs, node_weight, arc_weight)
+}
+
+/**
+ * Lexicographic product: (*u*, *v*) is connected to (*u'*, *v'*) when
+ * *u* is connected to *u'* in *A*, whatever *v* and *v'* are, or when
+ * *u* = *u'* and *v* is connected to *v'* in *B*.
+ */
+pub fn lexicographic<GA, GB, G, N, FN, FA>(a: GA, b: GB, node_weight: FN, arc_weight: FA) -> G
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    GB: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    FN: Fn(N, N) -> N,
+    FA: Fn(Option<N>, Option<N>) -> N,
+{
+    let factors = Factors::new(&a, &b);
+    let b_weights: HashMap<_, _> = factors
+        .b_arcs
+        .iter()
+        .map(|(i, j, w)| ((*i, *j), *w))
+        .collect();
+    let mut arcs = vec![];
+    for (u1, u2, wa) in &factors.a_arcs {
+        for v1 in 0..factors.nb {
+            for v2 in 0..factors.nb {
+                let wb = b_weights.get(&(v1, v2)).copied();
+                arcs.push(((*u1, v1), (*u2, v2), Some(*wa), wb));
+            }
+        }
+    }
+    for u in 0..factors.na {
+        for (v1, v2, wb) in &factors.b_arcs {
+            arcs.push(((u, *v1), (u, *v2), None, Some(*wb)));
+        }
+    }
+    factors.build(arcs, node_weight, arc_weight)
+}
+
+/**
+ * Build the line graph of *g*: each arc of *g* becomes a node and two nodes are
+ * connected when the corresponding arcs are adjacent. In direct graphs the
+ * arc (*i*, *j*) is connected to each arc (*j*, *k*), in undirect graphs two arcs
+ * are adjacent when they share an end. Each node weights as the original arc,
+ * each arc is weighted by *arc_weight*, called with the weights of the
+ * two adjacent original arcs. Undirect arcs are taken once, as (*i*, *j*) with
+ * *i* <= *j*. Return the line graph and the original arc of each node.
+ */
+pub fn line_graph<GA, G, N, F>(g: GA, arc_weight: F) -> (G, Vec<(usize, usize)>)
+where
+    GA: GraphVisitor<N> + GetGraphType,
+    G: Graph<N>,
+    N: Copy,
+    F: Fn(N, N) -> N,
+{
+    let gtype = g.graph_type();
+    let mut arcs = vec![];
+    g.arc_visitor(|i, j, w| {
+        if gtype == GraphType::Direct || i <= j {
+            arcs.push((i, j, w));
+        }
+    });
+
+    let mut incident: Vec<Vec<usize>> = vec![vec![]; g.node_count()];
+    for (k, (i, j, _)) in arcs.iter().enumerate() {
+        incident[*i].push(k);
+        if gtype == GraphType::Undirect && i != j {
+            incident[*j].push(k);
+        }
+    }
+
+    let mut line = G::new(arcs.len(), gtype);
+    line.update_all_nodes_weight(|k, _| arcs[k].2);
+    for (k, (_, j, w)) in arcs.iter().enumerate() {
+        match gtype {
+            GraphType::Direct => {
+                for l in &incident[*j] {
+                    line.add_new_arc(k, *l, arc_weight(*w, arcs[*l].2));
+                }
+            }
+            GraphType::Undirect => {
+                let (i, j) = (arcs[k].0, *j);
+                let mut adjacent: Vec<_> = incident[i]
+                    .iter()
+                    .chain(&incident[j])
+                    .filter(|l| **l > k)
+                    .copied()
+                    .collect();
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                for l in adjacent {
+                    line.add_new_arc(k, l, arc_weight(*w, arcs[l].2));
+                }
+            }
+        }
+    }
+    let mapping = arcs.into_iter().map(|(i, j, _)| (i, j)).collect();
+    (line, mapping)
+}
+
+type ProductArc<N> = ((usize, usize), (usize, usize), Option<N>, Option<N>);
+
+struct Factors<N> {
+    gtype: GraphType,
+    na: usize,
+    nb: usize,
+    a_nodes: Vec<N>,
+    b_nodes: Vec<N>,
+    a_arcs: Vec<(usize, usize, N)>,
+    b_arcs: Vec<(usize, usize, N)>,
+}
+
+impl<N: Copy> Factors<N> {
+    fn new<GA, GB>(a: &GA, b: &GB) -> Self
+    where
+        GA: GraphVisitor<N> + GetGraphType,
+        GB: GraphVisitor<N> + GetGraphType,
+    {
+        let gtype = a.graph_type();
+        assert_eq!(
+            gtype,
+            b.graph_type(),
+            "the factors of a product must have the same graph type"
+        );
+        let (a_nodes, a_arcs) = Self::collect(a);
+        let (b_nodes, b_arcs) = Self::collect(b);
+        Self {
+            gtype,
+            na: a_nodes.len(),
+            nb: b_nodes.len(),
+            a_nodes,
+            b_nodes,
+            a_arcs,
+            b_arcs,
+        }
+    }
+
+    fn collect<G: GraphVisitor<N>>(g: &G) -> (Vec<N>, Vec<(usize, usize, N)>) {
+        let mut nodes = Vec::with_capacity(g.node_count());
+        g.node_visitor(|_, n| nodes.push(n));
+        let mut arcs = Vec::with_capacity(g.arc_count());
+        g.arc_visitor(|i, j, w| arcs.push((i, j, w)));
+        (nodes, arcs)
+    }
+
+    fn cartesian_arcs(&self, arcs: &mut Vec<ProductArc<N>>) {
+        for (u1, u2, wa) in &self.a_arcs {
+            for v in 0..self.nb {
+                arcs.push(((*u1, v), (*u2, v), Some(*wa), None));
+            }
+        }
+        for u in 0..self.na {
+            for (v1, v2, wb) in &self.b_arcs {
+                arcs.push(((u, *v1), (u, *v2), None, Some(*wb)));
+            }
+        }
+    }
+
+    fn tensor_arcs(&self, arcs: &mut Vec<ProductArc<N>>) {
+        for (u1, u2, wa) in &self.a_arcs {
+            for (v1, v2, wb) in &self.b_arcs {
+                arcs.push(((*u1, *v1), (*u2, *v2), Some(*wa), Some(*wb)));
+            }
+        }
+    }
+
+    /**
+     * Both factors visit undirect arcs in both directions, so
+     * each undirect product arc is generated twice: insert it once.
+     */
+    fn build<G, FN, FA>(&self, mut arcs: Vec<ProductArc<N>>, node_weight: FN, arc_weight: FA) -> G
+    where
+        G: Graph<N>,
+        FN: Fn(N, N) -> N,
+        FA: Fn(Option<N>, Option<N>) -> N,
+    {
+        let nb = self.nb;
+        let mut graph = G::new(self.na * nb, self.gtype);
+        graph.update_all_nodes_weight(|k, _| {
+            let (u, v) = factor_nodes(k, nb);
+            node_weight(self.a_nodes[u], self.b_nodes[v])
+        });
+        arcs.sort_by_key(|((u1, v1), (u2, v2), _, _)| {
+            (product_node(*u1, *v1, nb), product_node(*u2, *v2, nb))
+        });
+        for ((u1, v1), (u2, v2), wa, wb) in arcs {
+            let src = product_node(u1, v1, nb);
+            let dst = product_node(u2, v2, nb);
+            if self.gtype == GraphType::Direct || src <= dst {
+                graph.add_new_arc(src, dst, arc_weight(wa, wb));
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::families::{complete, cycle, path};
+    use crate::{AdjList, MatrixGraph};
+
+    fn degrees(graph: &AdjList<u32>) -> Vec<usize> {
+        let mut degrees = vec![0; graph.node_count()];
+        graph.arc_visitor(|i, _, _| degrees[i] += 1);
+        degrees
+    }
+
+    fn sum(a: Option<u32>, b: Option<u32>) -> u32 {
+        a.unwrap_or(0) + b.unwrap_or(0)
+    }
+
+    #[test]
+    #[should_panic(expected = "the factors of a product must have the same graph type")]
+    fn test_mixed_factors() {
+        let a: AdjList<u32> = path(2, GraphType::Direct);
+        let b: AdjList<u32> = path(2, GraphType::Undirect);
+        let _: AdjList<u32> = cartesian(&a, &b, |_, _| 0, sum);
+    }
+
+    #[test]
+    fn test_node_index() {
+        assert_eq!(product_node(2, 1, 3), 7);
+        assert_eq!(factor_nodes(7, 3), (2, 1));
+    }
+
+    #[test]
+    fn test_cartesian() {
+        let mut a: AdjList<u32> = AdjList::new_direct(2);
+        a.update_all_nodes_weight(|i, _| 10 * (i as u32 + 1));
+        a.add_new_arc(0, 1, 5);
+        let mut b: AdjList<u32> = AdjList::new_direct(3);
+        b.update_all_nodes_weight(|i, _| i as u32);
+        b.add_new_arc(0, 1, 1);
+        b.add_new_arc(1, 2, 2);
+
+        let product: AdjList<u32> = cartesian(&a, &b, |x, y| x + y, sum);
+        let nodes: Vec<_> = product.node_iterator().map(|(_, w)| w).collect();
+        assert_eq!(nodes, vec![10, 11, 12, 20, 21, 22]);
+        let arcs: Vec<_> = product.arc_iterator().collect();
+        let expect = vec![
+            (0, 1, 1),
+            (0, 3, 5),
+            (1, 2, 2),
+            (1, 4, 5),
+            (2, 5, 5),
+            (3, 4, 1),
+            (4, 5, 2),
+        ];
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_torus() {
+        let a: AdjList<u32> = cycle(4, GraphType::Undirect);
+        let b: AdjList<u32> = cycle(5, GraphType::Undirect);
+        let torus: AdjList<u32> = cartesian(&a, &b, |_, _| 0, sum);
+        assert!(degrees(&torus).iter().all(|d| *d == 4));
+    }
+
+    #[test]
+    fn test_tensor_strong() {
+        let a: AdjList<u32> = path(3, GraphType::Undirect);
+        let b: AdjList<u32> = complete(2, GraphType::Undirect);
+        let product: AdjList<u32> = tensor(&a, &b, |_, _| 0, sum);
+        assert_eq!(degrees(&product), vec![1, 1, 2, 2, 1, 1]);
+        (&product).arc_visitor(|i, j, _| {
+            let (u1, v1) = factor_nodes(i, 2);
+            let (u2, v2) = factor_nodes(j, 2);
+            assert!(u1 != u2 && v1 != v2);
+        });
+
+        let product: MatrixGraph<u32> = strong(&a, &b, |_, _| 0, sum);
+        // 7 cartesian edges plus 4 tensor edges
+        assert_eq!(product.arc_iterator().count(), 2 * (7 + 4));
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        let a: AdjList<u32> = path(2, GraphType::Direct);
+        let mut b: AdjList<u32> = AdjList::new_direct(2);
+        b.add_new_arc(1, 0, 7);
+        let product: AdjList<u32> = lexicographic(&a, &b, |_, _| 0, sum);
+        let arcs: Vec<_> = product.arc_iterator().collect();
+        let expect = vec![
+            (0, 2, 0),
+            (0, 3, 0),
+            (1, 0, 7),
+            (1, 2, 7),
+            (1, 3, 0),
+            (3, 2, 7),
+        ];
+        assert_eq!(arcs, expect);
+    }
+
+    #[test]
+    fn test_line_graph() {
+        let mut star: AdjList<u32> = AdjList::new_undirect(4);
+        star.add_new_arc(0, 1, 1);
+        star.add_new_arc(0, 2, 2);
+        star.add_new_arc(3, 0, 3);
+        let (line, mapping): (AdjList<u32>, _) = line_graph(&star, |a, b| a * b);
+        assert_eq!(mapping, vec![(0, 1), (0, 2), (0, 3)]);
+        let nodes: Vec<_> = line.node_iterator().map(|(_, w)| w).collect();
+        assert_eq!(nodes, vec![1, 2, 3]);
+        let mut arcs: Vec<_> = line.arc_iterator().collect();
+        arcs.sort_unstable();
+        let expect = vec![
+            (0, 1, 2),
+            (0, 2, 3),
+            (1, 0, 2),
+            (1, 2, 6),
+            (2, 0, 3),
+            (2, 1, 6),
+        ];
+        assert_eq!(arcs, expect);
+
+        let path: MatrixGraph<u32> = path(4, GraphType::Direct);
+        let (line, mapping): (AdjList<u32>, _) = line_graph(&path, |_, _| 1);
+        assert_eq!(mapping, vec![(0, 1), (1, 2), (2, 3)]);
+        let arcs: Vec<_> = line.arc_iterator().map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(arcs, vec![(0, 1), (1, 2)]);
+    }
+}
@@ -0,0 +1,57 @@
+/*!
+ * Error reported when a graph cannot be read from a text format.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/**
+ * Describe why a graph could not be loaded: either
+ * the underlying reader failed or the input is malformed.
+ */
+#[derive(Debug)]
+pub enum ReadError {
+    /**
+     * The underlying reader failed.
+     */
+    Io(io::Error),
+    /**
+     * The input is malformed. *line* is the (1-based) number of the
+     * line where the problem was found.
+     */
+    Syntax { line: usize, message: String },
+}
+
+impl ReadError {
+    pub(crate) fn syntax<S: Into<String>>(line: usize, message: S) -> Self {
+        Self::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Syntax { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Syntax { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
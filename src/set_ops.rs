@@ -0,0 +1,209 @@
+/*!
+ * Set operations on the arcs of two graphs with the same node count
+ * and [GraphType](crate::GraphType). The resulting graph keeps the nodes,
+ * and their weights, of the graph the operation is called on.
+ */
+
+use super::GraphType;
+
+/**
+ * Combine the arc sets of two graphs.
+ *
+ * ```
+ * use simplegraph::{Graph, MatrixGraph};
+ * use simplegraph::set_ops::SetOps;
+ *
+ * let mut a = MatrixGraph::<u32>::new_direct(3);
+ * a.add_new_arc(0, 1, 1);
+ * a.add_new_arc(1, 2, 2);
+ * let mut b = MatrixGraph::<u32>::new_direct(3);
+ * b.add_new_arc(1, 2, 3);
+ * b.add_new_arc(2, 0, 4);
+ *
+ * let union = a.union(&b, |x, y| x.max(y));
+ * let arcs: Vec<_> = union.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 1), (1, 2, 3), (2, 0, 4)]);
+ * ```
+ *
+ * # Panics
+ * Binary operations panic when the two graphs have a different
+ * node count or [GraphType](crate::GraphType).
+ */
+pub trait SetOps<N>: Sized {
+    /**
+     * Arcs present in at least one graph. The weight of arcs
+     * present in both graphs is computed by *merge*, called
+     * with the weight in *self* and the weight in *other*.
+     */
+    fn union<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N;
+
+    /**
+     * Arcs present in both graphs, weighted by *merge*, called
+     * with the weight in *self* and the weight in *other*.
+     */
+    fn intersection<F>(&self, other: &Self, merge: F) -> Self
+    where
+        F: Fn(N, N) -> N;
+
+    /**
+     * Arcs present in *self* but not in *other*.
+     */
+    fn difference(&self, other: &Self) -> Self;
+
+    /**
+     * Arcs present in exactly one graph, with their original weight.
+     */
+    fn symmetric_difference(&self, other: &Self) -> Self;
+
+    /**
+     * Arcs, between distinct nodes, not present in the graph. New arcs
+     * weight [num_traits::Num::zero()](https://docs.rs/num/latest/num/traits/trait.Zero.html).
+     */
+    fn complement(&self) -> Self;
+}
+
+/**
+ * Weight of an arc in the result of a binary operation, given
+ * the arc's weight in each graph, or ```None``` when the arc is missing.
+ */
+pub(crate) fn union_weight<N, F>(a: Option<N>, b: Option<N>, merge: &F) -> Option<N>
+where
+    F: Fn(N, N) -> N,
+{
+    match (a, b) {
+        (Some(a), Some(b)) => Some(merge(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+pub(crate) fn intersection_weight<N, F>(a: Option<N>, b: Option<N>, merge: &F) -> Option<N>
+where
+    F: Fn(N, N) -> N,
+{
+    Some(merge(a?, b?))
+}
+
+pub(crate) fn difference_weight<N>(a: Option<N>, b: Option<N>) -> Option<N> {
+    match b {
+        Some(_) => None,
+        None => a,
+    }
+}
+
+pub(crate) fn symmetric_difference_weight<N>(a: Option<N>, b: Option<N>) -> Option<N> {
+    match (a, b) {
+        (Some(_), Some(_)) => None,
+        (a, b) => a.or(b),
+    }
+}
+
+pub(crate) fn check_compatible(a: (usize, GraphType), b: (usize, GraphType)) {
+    assert_eq!(a.0, b.0, "graphs with different node count");
+    assert_eq!(a.1, b.1, "graphs with different type");
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::visitor::GraphVisitor;
+    use crate::{AdjList, Graph, MatrixGraph};
+
+    fn make_graphs<G: Graph<i32>>(gtype: GraphType) -> (G, G) {
+        let mut a = G::new(4, gtype);
+        a.update_all_nodes_weight(|i, _| i as i32);
+        a.add_new_arc(0, 1, 1);
+        a.add_new_arc(1, 2, 2);
+        a.add_new_arc(2, 3, 3);
+        let mut b = G::new(4, gtype);
+        b.add_new_arc(1, 2, 20);
+        b.add_new_arc(2, 3, 30);
+        b.add_new_arc(3, 0, 40);
+        (a, b)
+    }
+
+    fn run_direct<G, I>(arcs: impl Fn(&G) -> I)
+    where
+        G: Graph<i32> + SetOps<i32>,
+        I: Iterator<Item = (usize, usize, i32)>,
+    {
+        let (a, b) = make_graphs::<G>(GraphType::Direct);
+        let union: Vec<_> = arcs(&a.union(&b, |x, y| x + y)).collect();
+        assert_eq!(union, vec![(0, 1, 1), (1, 2, 22), (2, 3, 33), (3, 0, 40)]);
+
+        let inter: Vec<_> = arcs(&a.intersection(&b, |_, y| y)).collect();
+        assert_eq!(inter, vec![(1, 2, 20), (2, 3, 30)]);
+
+        let diff: Vec<_> = arcs(&a.difference(&b)).collect();
+        assert_eq!(diff, vec![(0, 1, 1)]);
+
+        let sym: Vec<_> = arcs(&a.symmetric_difference(&b)).collect();
+        assert_eq!(sym, vec![(0, 1, 1), (3, 0, 40)]);
+
+        let comp: Vec<_> = arcs(&a.complement()).map(|(i, j, _)| (i, j)).collect();
+        let expect = vec![
+            (0, 2),
+            (0, 3),
+            (1, 0),
+            (1, 3),
+            (2, 0),
+            (2, 1),
+            (3, 0),
+            (3, 1),
+            (3, 2),
+        ];
+        assert_eq!(comp, expect);
+    }
+
+    fn run_undirect<G, I>(arcs: impl Fn(&G) -> I)
+    where
+        G: Graph<i32> + SetOps<i32>,
+        I: Iterator<Item = (usize, usize, i32)>,
+    {
+        let (a, b) = make_graphs::<G>(GraphType::Undirect);
+        let sym: Vec<_> = arcs(&a.symmetric_difference(&b)).collect();
+        assert_eq!(sym, vec![(0, 1, 1), (0, 3, 40), (1, 0, 1), (3, 0, 40)]);
+
+        let union = a.union(&b, |x, y| x * y);
+        assert_eq!(arcs(&union).count(), 8);
+        assert!(arcs(&union).any(|arc| arc == (3, 2, 90)));
+
+        let comp: Vec<_> = arcs(&a.complement()).map(|(i, j, _)| (i, j)).collect();
+        assert_eq!(comp, vec![(0, 2), (0, 3), (1, 3), (2, 0), (3, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn test_adj_list() {
+        run_direct::<AdjList<i32>, _>(|g| g.arc_iterator().collect::<Vec<_>>().into_iter());
+        run_undirect::<AdjList<i32>, _>(|g| {
+            let mut arcs: Vec<_> = g.arc_iterator().collect();
+            arcs.sort_by_key(|(i, j, _)| (*i, *j));
+            arcs.into_iter()
+        });
+
+        let (a, b) = make_graphs::<AdjList<i32>>(GraphType::Direct);
+        let union = a.union(&b, |x, _| x);
+        let nodes: Vec<_> = union.node_iterator().collect();
+        assert_eq!(nodes, vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_matrix_graph() {
+        run_direct::<MatrixGraph<i32>, _>(|g| g.arc_iterator().collect::<Vec<_>>().into_iter());
+        run_undirect::<MatrixGraph<i32>, _>(|g| g.arc_iterator().collect::<Vec<_>>().into_iter());
+
+        let (a, b) = make_graphs::<MatrixGraph<i32>>(GraphType::Undirect);
+        let inter = a.intersection(&b, |x, y| x + y);
+        assert_eq!((&inter).arc_count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "graphs with different type")]
+    fn test_incompatible() {
+        let a = AdjList::<i32>::new_direct(3);
+        let b = AdjList::<i32>::new_undirect(3);
+        a.difference(&b);
+    }
+}
@@ -0,0 +1,621 @@
+/*!
+ * Point-to-point shortest paths with
+ * [Dijkstra's algorithm](https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm),
+ * also in its bidirectional variant, and the *k* shortest loopless paths with
+ * [Yen's algorithm](https://en.wikipedia.org/wiki/Yen%27s_algorithm).
+ * Arc weights must not be negative. Paths are returned as the list of
+ * visited nodes, from source to destination, together with their cost.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::shortest_path::{dijkstra, k_shortest_paths};
+ *
+ * let mut graph = AdjList::<u32>::new_direct(4);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 3, 1);
+ * graph.add_new_arc(0, 2, 1);
+ * graph.add_new_arc(2, 3, 2);
+ * graph.add_new_arc(0, 3, 4);
+ *
+ * assert_eq!(dijkstra(&graph, 0, 3), Some((vec![0, 1, 3], 2)));
+ * let paths: Vec<_> = k_shortest_paths(&graph, 0, 3).collect();
+ * assert_eq!(paths, vec![(vec![0, 1, 3], 2), (vec![0, 2, 3], 3), (vec![0, 3], 4)]);
+ * ```
+ */
+
+use super::visitor::{GraphVisitor, SuccessorVisitor};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+/**
+ * Find the shortest path from *src* to *dst*, or ```None```
+ * if *dst* is not reachable from *src*.
+ */
+pub fn dijkstra<G, N>(graph: G, src: usize, dst: usize) -> Option<(Vec<usize>, N)>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    let banned_nodes = vec![false; graph.node_count()];
+    let (path, costs) = search(&graph, src, dst, &banned_nodes, &HashSet::new())?;
+    Some((path, *costs.last().unwrap()))
+}
+
+/**
+ * Return an iterator over the loopless paths from *src* to *dst* in increasing
+ * cost order. Paths are computed lazily: each call to ```next``` runs up to
+ * one Dijkstra search for each node of the previous path, so callers should
+ * stop as soon as they have enough paths, for example with ```take```.
+ */
+pub fn k_shortest_paths<G, N>(graph: G, src: usize, dst: usize) -> KShortestPaths<G, N>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    KShortestPaths {
+        graph,
+        src,
+        dst,
+        found: vec![],
+        candidates: BinaryHeap::new(),
+        seen: HashSet::new(),
+        done: false,
+    }
+}
+
+/**
+ * Iterator over the loopless paths between two nodes, in increasing
+ * cost order, see [k_shortest_paths].
+ */
+pub struct KShortestPaths<G, N> {
+    graph: G,
+    src: usize,
+    dst: usize,
+    // paths already returned, with the cost to reach each of their nodes
+    found: Vec<(Vec<usize>, Vec<N>)>,
+    candidates: BinaryHeap<Candidate<N>>,
+    seen: HashSet<Vec<usize>>,
+    done: bool,
+}
+
+impl<G, N> KShortestPaths<G, N>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    /**
+     * Add to the candidates each deviation from the last found path:
+     * the path follows the last one up to a spur node, then avoids
+     * the arcs taken by already found paths sharing the same root.
+     */
+    fn add_candidates(&mut self) {
+        let (last, last_costs) = self.found.last().unwrap();
+        let mut banned_nodes = vec![false; self.graph.node_count()];
+        for i in 0..last.len().saturating_sub(1) {
+            let root = &last[..=i];
+            let banned_arcs: HashSet<_> = self
+                .found
+                .iter()
+                .filter(|(path, _)| path.len() > i + 1 && &path[..=i] == root)
+                .map(|(path, _)| (path[i], path[i + 1]))
+                .collect();
+            let spur = search(&self.graph, last[i], self.dst, &banned_nodes, &banned_arcs);
+            if let Some((spur_path, spur_costs)) = spur {
+                let mut path = last[..i].to_vec();
+                path.extend(spur_path);
+                if self.seen.insert(path.clone()) {
+                    let mut costs = last_costs[..i].to_vec();
+                    costs.extend(spur_costs.into_iter().map(|c| c + last_costs[i]));
+                    self.candidates.push(Candidate { path, costs });
+                }
+            }
+            banned_nodes[last[i]] = true;
+        }
+    }
+}
+
+impl<G, N> Iterator for KShortestPaths<G, N>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    type Item = (Vec<usize>, N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = if self.found.is_empty() {
+            let banned_nodes = vec![false; self.graph.node_count()];
+            let first = search(
+                &self.graph,
+                self.src,
+                self.dst,
+                &banned_nodes,
+                &HashSet::new(),
+            );
+            if let Some((path, _)) = &first {
+                self.seen.insert(path.clone());
+            }
+            first
+        } else {
+            self.add_candidates();
+            self.candidates.pop().map(|c| (c.path, c.costs))
+        };
+        match next {
+            Some((path, costs)) => {
+                let cost = *costs.last().unwrap();
+                self.found.push((path.clone(), costs));
+                Some((path, cost))
+            }
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+/**
+ * Point-to-point shortest paths with a bidirectional Dijkstra search:
+ * a forward search from the source, over the arcs exiting each node, and a
+ * backward search from the destination, over the arcs entering each node,
+ * run alternately until the sum of the two queues' minimum costs reaches the
+ * cost of the best path found where the searches meet. On large graphs
+ * this settles far fewer nodes than [dijkstra].
+ *
+ * The incoming arcs of each node are collected once, when the search is
+ * created, so that it can answer any number of queries.
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::shortest_path::BidirectionalDijkstra;
+ *
+ * let mut graph = AdjList::<u32>::new_direct(4);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 3, 1);
+ * graph.add_new_arc(0, 2, 1);
+ * graph.add_new_arc(2, 3, 2);
+ *
+ * let mut search = BidirectionalDijkstra::new(&graph);
+ * assert_eq!(search.query(0, 3), Some((vec![0, 1, 3], 2)));
+ * assert_eq!(search.query(3, 0), None);
+ * ```
+ */
+pub struct BidirectionalDijkstra<G, N> {
+    graph: G,
+    incoming: Vec<Vec<(usize, N)>>,
+    settled: usize,
+}
+
+impl<G, N> BidirectionalDijkstra<G, N>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    pub fn new(graph: G) -> Self {
+        let mut incoming = vec![vec![]; graph.node_count()];
+        graph.arc_visitor(|i, j, w| incoming[j].push((i, w)));
+        Self {
+            graph,
+            incoming,
+            settled: 0,
+        }
+    }
+
+    /**
+     * Find the shortest path from *src* to *dst*, or ```None```
+     * if *dst* is not reachable from *src*.
+     */
+    pub fn query(&mut self, src: usize, dst: usize) -> Option<(Vec<usize>, N)> {
+        let count = self.incoming.len();
+        let mut forward: Side<N> = Side::new(count, src);
+        let mut backward: Side<N> = Side::new(count, dst);
+        // best path found so far: its cost and the arc joining the two searches
+        let mut best: Option<(N, usize, usize)> = (src == dst).then_some((N::zero(), src, dst));
+        self.settled = 0;
+
+        while let (Some(top_f), Some(top_b)) = (forward.top(), backward.top()) {
+            if best.is_some_and(|(cost, _, _)| top_f + top_b >= cost) {
+                break;
+            }
+            self.settled += 1;
+            if top_f <= top_b {
+                let (node, cost) = forward.settle();
+                self.graph.successor_visitor(node, |next, w| {
+                    forward.relax(node, next, cost + w);
+                    if let Some(rest) = backward.dist[next] {
+                        update(&mut best, cost + w + rest, node, next);
+                    }
+                });
+            } else {
+                let (node, cost) = backward.settle();
+                for (prev, w) in &self.incoming[node] {
+                    backward.relax(node, *prev, cost + *w);
+                    if let Some(rest) = forward.dist[*prev] {
+                        update(&mut best, rest + *w + cost, *prev, node);
+                    }
+                }
+            }
+        }
+
+        let (cost, last, first) = best?;
+        let mut path = forward.path(last);
+        path.reverse();
+        if last != first {
+            path.extend(backward.path(first));
+        }
+        Some((path, cost))
+    }
+
+    /**
+     * Return the number of nodes settled, by both
+     * searches, during the last query.
+     */
+    pub fn settled(&self) -> usize {
+        self.settled
+    }
+}
+
+fn update<N: PartialOrd>(best: &mut Option<(N, usize, usize)>, cost: N, last: usize, first: usize) {
+    if best.as_ref().is_none_or(|(curr, _, _)| cost < *curr) {
+        *best = Some((cost, last, first));
+    }
+}
+
+/**
+ * State of one direction of a bidirectional search.
+ */
+struct Side<N> {
+    dist: Vec<Option<N>>,
+    prev: Vec<usize>,
+    settled: Vec<bool>,
+    queue: BinaryHeap<Entry<N>>,
+}
+
+impl<N> Side<N>
+where
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    fn new(count: usize, root: usize) -> Self {
+        let mut dist = vec![None; count];
+        dist[root] = Some(N::zero());
+        let mut queue = BinaryHeap::new();
+        queue.push(Entry {
+            cost: N::zero(),
+            node: root,
+        });
+        Self {
+            dist,
+            prev: vec![usize::MAX; count],
+            settled: vec![false; count],
+            queue,
+        }
+    }
+
+    /**
+     * Minimum cost in the queue, after discarding settled nodes.
+     */
+    fn top(&mut self) -> Option<N> {
+        while let Some(entry) = self.queue.peek() {
+            if !self.settled[entry.node] {
+                return Some(entry.cost);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    fn settle(&mut self) -> (usize, N) {
+        let Entry { cost, node } = self.queue.pop().unwrap();
+        self.settled[node] = true;
+        (node, cost)
+    }
+
+    fn relax(&mut self, node: usize, next: usize, cost: N) {
+        if !self.settled[next] && self.dist[next].is_none_or(|curr| cost < curr) {
+            self.dist[next] = Some(cost);
+            self.prev[next] = node;
+            self.queue.push(Entry { cost, node: next });
+        }
+    }
+
+    /**
+     * Nodes from *node* back to the root of the search.
+     */
+    fn path(&self, mut node: usize) -> Vec<usize> {
+        let mut path = vec![node];
+        while self.prev[node] != usize::MAX {
+            node = self.prev[node];
+            path.push(node);
+        }
+        path
+    }
+}
+
+/**
+ * Dijkstra search from *src* to *dst* avoiding the banned nodes and arcs.
+ * Return the path and the cost to reach each of its nodes.
+ */
+fn search<G, N>(
+    graph: &G,
+    src: usize,
+    dst: usize,
+    banned_nodes: &[bool],
+    banned_arcs: &HashSet<(usize, usize)>,
+) -> Found<N>
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    search_counting(graph, src, dst, banned_nodes, banned_arcs).0
+}
+
+/**
+ * A path and the cost to reach each of its nodes.
+ */
+type Found<N> = Option<(Vec<usize>, Vec<N>)>;
+
+/**
+ * Same as [search], also return the number of settled nodes.
+ */
+fn search_counting<G, N>(
+    graph: &G,
+    src: usize,
+    dst: usize,
+    banned_nodes: &[bool],
+    banned_arcs: &HashSet<(usize, usize)>,
+) -> (Found<N>, usize)
+where
+    G: GraphVisitor<N> + SuccessorVisitor<N>,
+    N: num_traits::Num + Copy + PartialOrd,
+{
+    let count = graph.node_count();
+    let mut dist: Vec<Option<N>> = vec![None; count];
+    let mut prev = vec![usize::MAX; count];
+    let mut settled = vec![false; count];
+    let mut settled_count = 0;
+    let mut queue = BinaryHeap::new();
+    dist[src] = Some(N::zero());
+    queue.push(Entry {
+        cost: N::zero(),
+        node: src,
+    });
+    while let Some(Entry { cost, node }) = queue.pop() {
+        if settled[node] {
+            continue;
+        }
+        settled[node] = true;
+        settled_count += 1;
+        if node == dst {
+            break;
+        }
+        graph.successor_visitor(node, |next, weight| {
+            if banned_nodes[next] || settled[next] || banned_arcs.contains(&(node, next)) {
+                return;
+            }
+            let next_cost = cost + weight;
+            if dist[next].is_none_or(|curr| next_cost < curr) {
+                dist[next] = Some(next_cost);
+                prev[next] = node;
+                queue.push(Entry {
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        });
+    }
+
+    if !settled[dst] {
+        return (None, settled_count);
+    }
+    let mut path = vec![dst];
+    let mut curr = dst;
+    while curr != src {
+        curr = prev[curr];
+        path.push(curr);
+    }
+    path.reverse();
+    let costs = path.iter().map(|node| dist[*node].unwrap()).collect();
+    (Some((path, costs)), settled_count)
+}
+
+/**
+ * Queue entry, ordered to make [BinaryHeap] a min-heap on cost.
+ */
+pub(crate) struct Entry<N> {
+    pub(crate) cost: N,
+    pub(crate) node: usize,
+}
+
+impl<N: PartialOrd> Ord for Entry<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(&other.cost, &self.cost).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl<N: PartialOrd> PartialOrd for Entry<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: PartialOrd> PartialEq for Entry<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N: PartialOrd> Eq for Entry<N> {}
+
+/**
+ * Candidate path in Yen's algorithm, ordered to make [BinaryHeap] a min-heap
+ * on cost, ties are broken by the paths' lexicographic order.
+ */
+struct Candidate<N> {
+    path: Vec<usize>,
+    costs: Vec<N>,
+}
+
+impl<N: PartialOrd> Ord for Candidate<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let cost = compare(other.costs.last(), self.costs.last());
+        cost.then_with(|| other.path.cmp(&self.path))
+    }
+}
+
+impl<N: PartialOrd> PartialOrd for Candidate<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: PartialOrd> PartialEq for Candidate<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N: PartialOrd> Eq for Candidate<N> {}
+
+/**
+ * Weights are only partially ordered: treat incomparable ones, like NaN, as equal.
+ */
+fn compare<T: PartialOrd>(a: T, b: T) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::families::grid_2d;
+    use crate::path_cost::{is_simple_path, path_cost};
+    use crate::{AdjList, Graph, GraphType, MatrixGraph};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashMap;
+
+    /**
+     * The example graph from Yen's algorithm Wikipedia page,
+     * with nodes C, D, E, F, G, H numbered from 0.
+     */
+    fn yen_example<G: Graph<u32>>() -> G {
+        let mut graph = G::new(6, GraphType::Direct);
+        for (i, j, w) in [
+            (0, 1, 3),
+            (0, 2, 2),
+            (1, 3, 4),
+            (2, 1, 1),
+            (2, 3, 2),
+            (2, 4, 3),
+            (3, 4, 2),
+            (3, 5, 1),
+            (4, 5, 2),
+        ] {
+            graph.add_new_arc(i, j, w);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let graph: AdjList<u32> = yen_example();
+        assert_eq!(dijkstra(&graph, 0, 5), Some((vec![0, 2, 3, 5], 5)));
+        assert_eq!(dijkstra(&graph, 2, 2), Some((vec![2], 0)));
+        assert_eq!(dijkstra(&graph, 5, 0), None);
+
+        let mut graph = MatrixGraph::<f64>::new_undirect(3);
+        graph.add_new_arc(0, 1, 0.5);
+        graph.add_new_arc(1, 2, 0.25);
+        graph.add_new_arc(0, 2, 1.0);
+        assert_eq!(dijkstra(&graph, 2, 0), Some((vec![2, 1, 0], 0.75)));
+    }
+
+    #[test]
+    fn test_yen() {
+        let graph: MatrixGraph<u32> = yen_example();
+        let paths: Vec<_> = k_shortest_paths(&graph, 0, 5).take(3).collect();
+        let expect = vec![
+            (vec![0, 2, 3, 5], 5),
+            (vec![0, 2, 4, 5], 7),
+            (vec![0, 1, 3, 5], 8),
+        ];
+        assert_eq!(paths, expect);
+
+        let graph: AdjList<u32> = yen_example();
+        let paths: Vec<_> = k_shortest_paths(&graph, 0, 5).collect();
+        assert_eq!(paths.len(), 7);
+        for pair in paths.windows(2) {
+            assert!(pair[0].1 <= pair[1].1);
+        }
+        let unique: HashSet<_> = paths.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(unique.len(), 7);
+
+        assert_eq!(k_shortest_paths(&graph, 5, 0).next(), None);
+        let paths: Vec<_> = k_shortest_paths(&graph, 3, 3).collect();
+        assert_eq!(paths, vec![(vec![3], 0)]);
+    }
+
+    #[test]
+    fn test_yen_grid() {
+        // the number of monotone paths across a 3x3 grid is 6,
+        // all with the same cost
+        let (mut graph, _): (AdjList<u32>, _) = grid_2d(3, 3, GraphType::Undirect);
+        graph.update_all_arcs_weight(|_, _, _| 1);
+        let paths: Vec<_> = k_shortest_paths(&graph, 0, 8).take(10).collect();
+        assert_eq!(paths.len(), 10);
+        assert!(paths[..6].iter().all(|(_, cost)| *cost == 4));
+        assert!(paths[6..].iter().all(|(_, cost)| *cost > 4));
+        for (path, cost) in &paths {
+            assert!(is_simple_path(&graph, path));
+            assert_eq!(path_cost(&graph, path), Ok(*cost));
+        }
+    }
+
+    #[test]
+    fn test_bidirectional() {
+        let graph: AdjList<u32> = yen_example();
+        let mut search = BidirectionalDijkstra::new(&graph);
+        for src in 0..6 {
+            for dst in 0..6 {
+                assert_eq!(search.query(src, dst), dijkstra(&graph, src, dst));
+            }
+        }
+        assert_eq!(search.query(4, 4), Some((vec![4], 0)));
+        assert_eq!(search.settled(), 0);
+    }
+
+    #[test]
+    fn test_bidirectional_settles_fewer_nodes() {
+        let mut rng = StdRng::seed_from_u64(49);
+        let side = 60;
+        for gtype in [GraphType::Undirect, GraphType::Direct] {
+            let (mut graph, _): (AdjList<u64>, _) = grid_2d(side, side, gtype);
+            // random weights, the same in both directions of undirect arcs
+            let mut weights = HashMap::new();
+            (&graph).arc_visitor(|i, j, _| {
+                weights
+                    .entry((i.min(j), i.max(j)))
+                    .or_insert_with(|| rng.gen_range(1..1_000_000u64));
+            });
+            graph.update_all_arcs_weight(|i, j, _| weights[&(i.min(j), i.max(j))]);
+            let count = side * side;
+            let mut search = BidirectionalDijkstra::new(&graph);
+            let banned_nodes = vec![false; count];
+            let (mut plain, mut bidirectional) = (0, 0);
+            for _ in 0..20 {
+                let src = rng.gen_range(0..count);
+                let dst = rng.gen_range(0..count);
+                let (expect, settled) =
+                    search_counting(&&graph, src, dst, &banned_nodes, &HashSet::new());
+                let expect = expect.map(|(path, costs)| (path, *costs.last().unwrap()));
+                assert_eq!(search.query(src, dst), expect);
+                plain += settled;
+                bidirectional += search.settled();
+            }
+            // on a grid each search covers about half the area of the plain one
+            assert!(3 * bidirectional < 2 * plain, "{bidirectional} vs {plain}");
+        }
+    }
+}
@@ -0,0 +1,336 @@
+/*!
+ * [Transitive closure](https://en.wikipedia.org/wiki/Transitive_closure#In_graph_theory)
+ * and [transitive reduction](https://en.wikipedia.org/wiki/Transitive_reduction).
+ *
+ * ```
+ * use simplegraph::{AdjList, Graph};
+ * use simplegraph::transitive::{transitive_closure, transitive_reduction};
+ *
+ * let mut deps = AdjList::<u32>::new_direct(3);
+ * deps.add_new_arc(0, 1, 1);
+ * deps.add_new_arc(1, 2, 1);
+ * deps.add_new_arc(0, 2, 1);
+ *
+ * let closure = transitive_closure(&deps);
+ * assert!(closure.has_arc(0, 2));
+ * assert!(!closure.has_arc(2, 0));
+ *
+ * let reduction = transitive_reduction(&deps).unwrap();
+ * let arcs: Vec<_> = reduction.arc_iterator().collect();
+ * assert_eq!(arcs, vec![(0, 1, 1), (1, 2, 1)]);
+ * ```
+ */
+
+use super::visitor::GraphVisitor;
+use super::{AdjList, GetGraphType, Graph, GraphType, MatrixGraph};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/**
+ * Error returned by [transitive_reduction] when
+ * the graph is not acyclic.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    cycle: Vec<usize>,
+}
+
+impl CycleError {
+    /**
+     * Nodes on a cycle found in the graph, in arc order:
+     * the last node is connected to the first.
+     */
+    pub fn cycle(&self) -> &[usize] {
+        &self.cycle
+    }
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph contains a cycle:")?;
+        for node in &self.cycle {
+            write!(f, " {node} ->")?;
+        }
+        write!(f, " {}", self.cycle[0])
+    }
+}
+
+impl Error for CycleError {}
+
+/**
+ * Compute the transitive closure of *graph*: arc (*i*, *j*) is present
+ * when *j* is reachable from *i* through at least one arc. Arcs of *graph*
+ * keep their weight, new arcs weight
+ * [num_traits::Num::zero()](https://docs.rs/num/latest/num/traits/trait.Zero.html).
+ * In direct graphs a node reaches itself only when it lies on a cycle,
+ * undirect closures never contain self loops unless *graph* does.
+ *
+ * The closure is computed by Warshall's algorithm on rows packed in bitsets,
+ * in O(*n*^3 / 64) time.
+ */
+pub fn transitive_closure<G, N>(graph: G) -> MatrixGraph<N>
+where
+    G: GraphVisitor<N> + GetGraphType,
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    let gtype = graph.graph_type();
+    let count = graph.node_count();
+    let mut closure = MatrixGraph::new(count, gtype);
+    let mut nodes = Vec::with_capacity(count);
+    graph.node_visitor(|_, w| nodes.push(w));
+    closure.update_all_nodes_weight(|i, _| nodes[i]);
+
+    let mut rows = vec![BitSet::new(count); count];
+    graph.arc_visitor(|i, j, w| {
+        rows[i].insert(j);
+        closure.add_new_arc(i, j, w);
+    });
+
+    for k in 0..count {
+        let row_k = rows[k].clone();
+        for row in rows.iter_mut() {
+            if row.contains(k) {
+                row.union_with(&row_k);
+            }
+        }
+    }
+
+    for (i, row) in rows.iter().enumerate() {
+        for j in row.iter() {
+            if gtype == GraphType::Direct || i != j {
+                closure.add_new_arc(i, j, N::zero());
+            }
+        }
+    }
+    closure
+}
+
+/**
+ * Compute the transitive reduction of the direct acyclic *graph*:
+ * the graph with the fewest arcs and the same reachability. Since *graph*
+ * is acyclic the reduction is unique and it is a subgraph of *graph*:
+ * remaining arcs keep their weight and nodes keep their weight.
+ * Return a [CycleError] if *graph* contains a cycle.
+ *
+ * # Panics
+ * Panics if *graph* is undirect.
+ */
+pub fn transitive_reduction<G, N>(graph: G) -> Result<AdjList<N>, CycleError>
+where
+    G: GraphVisitor<N> + GetGraphType,
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    assert_eq!(
+        graph.graph_type(),
+        GraphType::Direct,
+        "transitive reduction requires a direct graph"
+    );
+    let count = graph.node_count();
+    let mut successors = vec![vec![]; count];
+    graph.arc_visitor(|i, j, w| successors[i].push((j, w)));
+    let order = topological_order(&successors)?;
+
+    // reach[i] holds the nodes reachable from i through at least one arc
+    let mut reach = vec![BitSet::new(count); count];
+    for i in order.iter().rev() {
+        let mut row = BitSet::new(count);
+        for (j, _) in &successors[*i] {
+            row.insert(*j);
+            row.union_with(&reach[*j]);
+        }
+        reach[*i] = row;
+    }
+
+    let mut reduction = AdjList::new_direct(count);
+    let mut nodes = Vec::with_capacity(count);
+    graph.node_visitor(|_, w| nodes.push(w));
+    reduction.update_all_nodes_weight(|i, _| nodes[i]);
+    for (i, succ) in successors.iter().enumerate() {
+        // nodes reachable from i through a path of two or more arcs
+        let mut indirect = BitSet::new(count);
+        for (j, _) in succ {
+            indirect.union_with(&reach[*j]);
+        }
+        for (j, w) in succ {
+            if !indirect.contains(*j) {
+                reduction.add_new_arc(i, *j, *w);
+            }
+        }
+    }
+    Ok(reduction)
+}
+
+/**
+ * Return the nodes in topological order, or a cycle
+ * found by the depth first search.
+ */
+fn topological_order<N>(successors: &[Vec<(usize, N)>]) -> Result<Vec<usize>, CycleError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Color {
+        White,
+        Gray,
+        Black,
+    }
+
+    let count = successors.len();
+    let mut color = vec![Color::White; count];
+    let mut post_order = Vec::with_capacity(count);
+    let mut stack: Vec<(usize, usize)> = vec![];
+    for root in 0..count {
+        if color[root] != Color::White {
+            continue;
+        }
+        color[root] = Color::Gray;
+        stack.push((root, 0));
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            if let Some((succ, _)) = successors[node].get(*next) {
+                *next += 1;
+                match color[*succ] {
+                    Color::White => {
+                        color[*succ] = Color::Gray;
+                        stack.push((*succ, 0));
+                    }
+                    Color::Gray => {
+                        let start = stack.iter().position(|(n, _)| n == succ).unwrap();
+                        let cycle = stack[start..].iter().map(|(n, _)| *n).collect();
+                        return Err(CycleError { cycle });
+                    }
+                    Color::Black => {}
+                }
+            } else {
+                color[node] = Color::Black;
+                post_order.push(node);
+                stack.pop();
+            }
+        }
+    }
+    post_order.reverse();
+    Ok(post_order)
+}
+
+#[derive(Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(size: usize) -> Self {
+        Self {
+            words: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(k, word)| {
+            (0..64)
+                .filter(move |b| word & (1 << b) != 0)
+                .map(move |b| k * 64 + b)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::families::path;
+
+    #[test]
+    fn test_closure() {
+        let mut graph = AdjList::<i32>::new_direct(5);
+        graph.update_all_nodes_weight(|i, _| i as i32);
+        graph.add_new_arc(0, 1, 5);
+        graph.add_new_arc(1, 2, 6);
+        graph.add_new_arc(2, 1, 7);
+        graph.add_new_arc(3, 4, 8);
+
+        let closure = transitive_closure(&graph);
+        let arcs: Vec<_> = closure.arc_iterator().collect();
+        let expect = vec![
+            (0, 1, 5),
+            (0, 2, 0),
+            (1, 1, 0),
+            (1, 2, 6),
+            (2, 1, 7),
+            (2, 2, 0),
+            (3, 4, 8),
+        ];
+        assert_eq!(arcs, expect);
+        let nodes: Vec<_> = closure.node_iterator().map(|(_, w)| w).collect();
+        assert_eq!(nodes, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_closure_large() {
+        // a path longer than a bitset word
+        let graph: MatrixGraph<u32> = path(150, GraphType::Direct);
+        let closure = transitive_closure(&graph);
+        assert_eq!((&closure).arc_count(), 150 * 149 / 2);
+        assert!(closure.has_arc(3, 140));
+        assert!(!closure.has_arc(140, 3));
+
+        let graph: AdjList<u32> = path(70, GraphType::Undirect);
+        let closure = transitive_closure(&graph);
+        assert_eq!((&closure).arc_count(), 70 * 69);
+    }
+
+    #[test]
+    fn test_reduction() {
+        let mut graph = MatrixGraph::<u32>::new_direct(5);
+        for i in 0..5 {
+            for j in (i + 1)..5 {
+                graph.add_new_arc(i, j, (10 * i + j) as u32);
+            }
+        }
+        let reduction = transitive_reduction(&graph).unwrap();
+        let arcs: Vec<_> = reduction.arc_iterator().collect();
+        assert_eq!(arcs, vec![(0, 1, 1), (1, 2, 12), (2, 3, 23), (3, 4, 34)]);
+
+        // a diamond has no redundant arcs
+        let mut graph = AdjList::<u32>::new_direct(4);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(0, 2, 1);
+        graph.add_new_arc(1, 3, 1);
+        graph.add_new_arc(2, 3, 1);
+        graph.add_new_arc(0, 3, 1);
+        let reduction = transitive_reduction(&graph).unwrap();
+        assert_eq!((&reduction).arc_count(), 4);
+        assert!(reduction.arc_iterator().all(|(i, j, _)| (i, j) != (0, 3)));
+    }
+
+    #[test]
+    fn test_reduction_cycle() {
+        let mut graph = AdjList::<u32>::new_direct(5);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 1);
+        graph.add_new_arc(2, 3, 1);
+        graph.add_new_arc(3, 1, 1);
+        graph.add_new_arc(3, 4, 1);
+        let err = transitive_reduction(&graph).unwrap_err();
+        assert_eq!(err.cycle(), &[1, 2, 3]);
+        assert_eq!(err.to_string(), "graph contains a cycle: 1 -> 2 -> 3 -> 1");
+    }
+
+    #[test]
+    #[should_panic(expected = "transitive reduction requires a direct graph")]
+    fn test_reduction_undirect() {
+        let graph = AdjList::<u32>::new_undirect(3);
+        let _ = transitive_reduction(&graph);
+    }
+}
@@ -0,0 +1,687 @@
+/*!
+ * Solve the [traveling salesman problem](https://en.wikipedia.org/wiki/Travelling_salesman_problem)
+ * on complete [MatrixGraph]s, like the ones loaded by [read_tsplib](crate::tsplib::read_tsplib).
+ *
+ * Tours are closed and contain each node once, the first node is not repeated
+ * at the end, as in [TourCost], which is used to evaluate them. Local search
+ * compares negative cost changes, so it requires signed weights. The construction
+ * heuristics build a tour from scratch, the local search procedures improve
+ * an existing tour within a [Budget] and [held_karp] finds an optimal tour
+ * for small instances.
+ *
+ * ```
+ * use simplegraph::{Graph, MatrixGraph};
+ * use simplegraph::path_cost::TourCost;
+ * use simplegraph::tsp::{held_karp, local_search, nearest_neighbour, Budget};
+ *
+ * let points: [(i32, i32); 6] = [(0, 0), (4, 0), (1, 1), (4, 3), (0, 3), (2, 2)];
+ * let mut graph = MatrixGraph::<i32>::new_undirect(points.len());
+ * for (i, p) in points.iter().enumerate() {
+ *     for (j, q) in points.iter().enumerate().skip(i + 1) {
+ *         graph.add_new_arc(i, j, (p.0 - q.0).abs() + (p.1 - q.1).abs());
+ *     }
+ * }
+ *
+ * let mut tour = nearest_neighbour(&graph, 0);
+ * local_search(&graph, &mut tour, Budget::unlimited());
+ * let (optimum, _) = held_karp(&graph).unwrap();
+ * assert_eq!(TourCost::new(&graph, &tour).length(), optimum);
+ * ```
+ */
+
+use super::path_cost::{ArcCost, TourCost};
+use super::visitor::GraphVisitor;
+use super::{GetGraphType, GraphType, MatrixGraph};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+/**
+ * Limit the work done by a local search procedure: the search stops
+ * when it reaches a local optimum or, whichever comes first, after
+ * applying *max_moves* improving moves or after running for *time_limit*.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Budget {
+    pub max_moves: Option<usize>,
+    pub time_limit: Option<Duration>,
+}
+
+impl Budget {
+    /**
+     * Run until a local optimum is reached.
+     */
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Apply at most *max_moves* improving moves.
+     */
+    pub fn moves(max_moves: usize) -> Self {
+        Self {
+            max_moves: Some(max_moves),
+            time_limit: None,
+        }
+    }
+
+    /**
+     * Stop after running for *time_limit*.
+     */
+    pub fn time(time_limit: Duration) -> Self {
+        Self {
+            max_moves: None,
+            time_limit: Some(time_limit),
+        }
+    }
+}
+
+struct Tracker {
+    budget: Budget,
+    start: Instant,
+    moves: usize,
+}
+
+impl Tracker {
+    fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            start: Instant::now(),
+            moves: 0,
+        }
+    }
+
+    fn exhausted(&self) -> bool {
+        let moves = self.budget.max_moves.is_some_and(|max| self.moves >= max);
+        let time = self
+            .budget
+            .time_limit
+            .is_some_and(|limit| self.start.elapsed() >= limit);
+        moves || time
+    }
+}
+
+/**
+ * Build a tour starting from *start* and moving, at each step,
+ * to the nearest node not visited yet. Runs in O(*n*^2).
+ */
+pub fn nearest_neighbour<N>(graph: &MatrixGraph<N>, start: usize) -> Vec<usize>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    let count = graph.node_count();
+    if count == 0 {
+        return vec![];
+    }
+    let mut visited = vec![false; count];
+    let mut tour = Vec::with_capacity(count);
+    let mut curr = start;
+    visited[curr] = true;
+    tour.push(curr);
+    for _ in 1..count {
+        let next = (0..count)
+            .filter(|j| !visited[*j])
+            .min_by(|a, b| compare(graph.cost(curr, *a), graph.cost(curr, *b)))
+            .unwrap();
+        visited[next] = true;
+        tour.push(next);
+        curr = next;
+    }
+    tour
+}
+
+/**
+ * Build a tour starting from *start* alone and inserting, at each step,
+ * the node not in the tour whose insertion increases the tour length
+ * the least, in the position where it does so.
+ */
+pub fn cheapest_insertion<N>(graph: &MatrixGraph<N>, start: usize) -> Vec<usize>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    let count = graph.node_count();
+    if count == 0 {
+        return vec![];
+    }
+    // the tour as a successor list, the insertion point of a node
+    // is identified by the node it follows
+    let mut next = vec![start; count];
+    let mut in_tour = vec![false; count];
+    in_tour[start] = true;
+    let cost = |a: usize, b: usize| {
+        if a == b {
+            N::zero()
+        } else {
+            graph.cost(a, b)
+        }
+    };
+    // the added and removed costs, kept apart to support unsigned weights
+    let insertion = |next: &[usize], node: usize, after: usize| {
+        let before = next[after];
+        let added = cost(after, node) + cost(node, before);
+        (added, cost(after, before))
+    };
+    let cheaper = |a: (N, N), b: (N, N)| a.0 + b.1 < b.0 + a.1;
+    // for each node outside the tour, its cheapest insertion
+    let mut best: Vec<((N, N), usize)> = (0..count)
+        .map(|node| (insertion(&next, node, start), start))
+        .collect();
+
+    for _ in 1..count {
+        let node = (0..count)
+            .filter(|node| !in_tour[*node])
+            .reduce(|a, b| if cheaper(best[b].0, best[a].0) { b } else { a })
+            .unwrap();
+        let after = best[node].1;
+        next[node] = next[after];
+        next[after] = node;
+        in_tour[node] = true;
+
+        for other in (0..count).filter(|other| !in_tour[*other]) {
+            if best[other].1 == after {
+                // its insertion point is gone: look for a new one
+                let mut curr = start;
+                best[other] = (insertion(&next, other, curr), curr);
+                loop {
+                    curr = next[curr];
+                    if curr == start {
+                        break;
+                    }
+                    let delta = insertion(&next, other, curr);
+                    if cheaper(delta, best[other].0) {
+                        best[other] = (delta, curr);
+                    }
+                }
+            } else {
+                for point in [after, node] {
+                    let delta = insertion(&next, other, point);
+                    if cheaper(delta, best[other].0) {
+                        best[other] = (delta, point);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut tour = Vec::with_capacity(count);
+    let mut curr = start;
+    for _ in 0..count {
+        tour.push(curr);
+        curr = next[curr];
+    }
+    tour
+}
+
+/**
+ * Build a tour in the style of the
+ * [Christofides algorithm](https://en.wikipedia.org/wiki/Christofides_algorithm):
+ * join a minimum spanning tree and a matching of its odd-degree nodes,
+ * then shortcut an Eulerian circuit of the resulting multigraph. The
+ * matching is built greedily, instead of being a minimum weight perfect
+ * matching, so the 3/2 approximation bound does not hold.
+ *
+ * # Panics
+ * Panics if *graph* is direct.
+ */
+pub fn christofides<N>(graph: &MatrixGraph<N>) -> Vec<usize>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    assert_eq!(
+        graph.graph_type(),
+        GraphType::Undirect,
+        "christofides requires an undirect graph"
+    );
+    let count = graph.node_count();
+    if count == 0 {
+        return vec![];
+    }
+    let mut edges = minimum_spanning_tree(graph);
+
+    let mut degree = vec![0; count];
+    for (i, j) in &edges {
+        degree[*i] += 1;
+        degree[*j] += 1;
+    }
+    let odd: Vec<_> = (0..count).filter(|i| degree[*i] % 2 == 1).collect();
+    let mut pairs = vec![];
+    for (k, i) in odd.iter().enumerate() {
+        for j in &odd[(k + 1)..] {
+            pairs.push((*i, *j));
+        }
+    }
+    pairs.sort_by(|(a, b), (c, d)| compare(graph.cost(*a, *b), graph.cost(*c, *d)));
+    let mut matched = vec![false; count];
+    for (i, j) in pairs {
+        if !matched[i] && !matched[j] {
+            matched[i] = true;
+            matched[j] = true;
+            edges.push((i, j));
+        }
+    }
+
+    let mut visited = vec![false; count];
+    eulerian_circuit(count, &edges)
+        .into_iter()
+        .filter(|node| !std::mem::replace(&mut visited[*node], true))
+        .collect()
+}
+
+/**
+ * Prim's algorithm in O(*n*^2), suited to complete graphs.
+ */
+fn minimum_spanning_tree<N>(graph: &MatrixGraph<N>) -> Vec<(usize, usize)>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    let count = graph.node_count();
+    let mut in_tree = vec![false; count];
+    let mut closest: Vec<(N, usize)> = (0..count).map(|j| (graph.cost(0, j), 0)).collect();
+    in_tree[0] = true;
+    let mut edges = Vec::with_capacity(count.saturating_sub(1));
+    for _ in 1..count {
+        let node = (0..count)
+            .filter(|j| !in_tree[*j])
+            .min_by(|a, b| compare(closest[*a].0, closest[*b].0))
+            .unwrap();
+        in_tree[node] = true;
+        edges.push((closest[node].1, node));
+        for j in (0..count).filter(|j| !in_tree[*j]) {
+            let cost = graph.cost(node, j);
+            if cost < closest[j].0 {
+                closest[j] = (cost, node);
+            }
+        }
+    }
+    edges
+}
+
+/**
+ * Hierholzer's algorithm on a connected multigraph with even degrees.
+ */
+fn eulerian_circuit(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut incident = vec![vec![]; count];
+    for (k, (i, j)) in edges.iter().enumerate() {
+        incident[*i].push((*j, k));
+        incident[*j].push((*i, k));
+    }
+    let mut used = vec![false; edges.len()];
+    let mut circuit = Vec::with_capacity(edges.len() + 1);
+    let mut stack = vec![0];
+    while let Some(node) = stack.last().copied() {
+        match incident[node].pop() {
+            Some((_, k)) if used[k] => {}
+            Some((other, k)) => {
+                used[k] = true;
+                stack.push(other);
+            }
+            None => {
+                circuit.push(node);
+                stack.pop();
+            }
+        }
+    }
+    circuit
+}
+
+/**
+ * Improve *tour* with [2-opt](https://en.wikipedia.org/wiki/2-opt) moves,
+ * applying the first improving move found, until a local optimum
+ * is reached or the *budget* is exhausted. On direct graphs the cost
+ * of the reversed segment is taken into account.
+ * Return the number of applied moves.
+ */
+pub fn two_opt<N>(graph: &MatrixGraph<N>, tour: &mut [usize], budget: Budget) -> usize
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd + num_traits::Signed,
+{
+    let mut tracker = Tracker::new(budget);
+    two_opt_search(graph, tour, &mut tracker);
+    tracker.moves
+}
+
+fn two_opt_search<N>(graph: &MatrixGraph<N>, tour: &mut [usize], tracker: &mut Tracker) -> bool
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd + num_traits::Signed,
+{
+    let count = tour.len();
+    let mut improved = false;
+    'search: while !tracker.exhausted() {
+        let costs = TourCost::new(graph, tour);
+        for i in 0..count {
+            if tracker.exhausted() {
+                break 'search;
+            }
+            for j in (i + 2)..count {
+                if costs.two_opt_delta(i, j) < N::zero() {
+                    tour[(i + 1)..=j].reverse();
+                    tracker.moves += 1;
+                    improved = true;
+                    continue 'search;
+                }
+            }
+        }
+        break;
+    }
+    improved
+}
+
+/**
+ * Improve *tour* with [Or-opt](https://en.wikipedia.org/wiki/Or-opt) moves,
+ * moving segments of one to three nodes elsewhere in the tour, possibly
+ * reversed, until a local optimum is reached or the *budget* is exhausted.
+ * Return the number of applied moves.
+ */
+pub fn or_opt<N>(graph: &MatrixGraph<N>, tour: &mut [usize], budget: Budget) -> usize
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd + num_traits::Signed,
+{
+    let mut tracker = Tracker::new(budget);
+    or_opt_search(graph, tour, &mut tracker);
+    tracker.moves
+}
+
+fn or_opt_search<N>(graph: &MatrixGraph<N>, tour: &mut [usize], tracker: &mut Tracker) -> bool
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd + num_traits::Signed,
+{
+    let count = tour.len();
+    let mut improved = false;
+    'search: while !tracker.exhausted() {
+        let costs = TourCost::new(graph, tour);
+        for start in 0..count {
+            if tracker.exhausted() {
+                break 'search;
+            }
+            for len in (1..=3).filter(|len| len + 2 <= count) {
+                for offset in len..(count - 1) {
+                    let target = (start + offset) % count;
+                    for reversed in [false, true] {
+                        if costs.or_opt_delta(start, len, target, reversed) < N::zero() {
+                            apply_or_opt(tour, start, len, offset, reversed);
+                            tracker.moves += 1;
+                            improved = true;
+                            continue 'search;
+                        }
+                    }
+                }
+            }
+        }
+        break;
+    }
+    improved
+}
+
+/**
+ * Move the *len* nodes from position *start* right after
+ * the node *offset* positions after *start*.
+ */
+fn apply_or_opt(tour: &mut [usize], start: usize, len: usize, offset: usize, reversed: bool) {
+    let count = tour.len();
+    let at = |k: usize| tour[(start + k) % count];
+    let mut segment: Vec<_> = (0..len).map(at).collect();
+    if reversed {
+        segment.reverse();
+    }
+    let mut moved: Vec<_> = (len..=offset).map(at).collect();
+    moved.extend(segment);
+    moved.extend(((offset + 1)..count).map(at));
+    tour.copy_from_slice(&moved);
+}
+
+/**
+ * Alternate [two_opt] and [or_opt] until the tour is a local optimum for
+ * both neighbourhoods or the *budget*, shared by both, is exhausted.
+ * Return the number of applied moves.
+ */
+pub fn local_search<N>(graph: &MatrixGraph<N>, tour: &mut [usize], budget: Budget) -> usize
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd + num_traits::Signed,
+{
+    let mut tracker = Tracker::new(budget);
+    loop {
+        two_opt_search(graph, tour, &mut tracker);
+        if !or_opt_search(graph, tour, &mut tracker) || tracker.exhausted() {
+            break;
+        }
+    }
+    tracker.moves
+}
+
+/**
+ * Largest graph accepted by [held_karp]: its tables then take
+ * about 40 MB with 64 bit weights, doubling with each extra node.
+ */
+pub const HELD_KARP_MAX_NODES: usize = 18;
+
+/**
+ * Find an optimal tour with the
+ * [Held-Karp algorithm](https://en.wikipedia.org/wiki/Held%E2%80%93Karp_algorithm),
+ * in O(2^*n* *n*^2) time and O(2^*n* *n*) memory. Unlike the heuristics
+ * *graph* does not need to be complete. Return the length of the
+ * optimal tour and the tour, starting from node 0, or ```None```
+ * if *graph* has no Hamiltonian cycle.
+ *
+ * # Panics
+ * Panics if *graph* has more than [HELD_KARP_MAX_NODES] nodes.
+ */
+pub fn held_karp<N>(graph: &MatrixGraph<N>) -> Option<(N, Vec<usize>)>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize + PartialOrd,
+{
+    let count = graph.node_count();
+    assert!(
+        count <= HELD_KARP_MAX_NODES,
+        "held-karp supports at most {HELD_KARP_MAX_NODES} nodes"
+    );
+    match count {
+        0 => return Some((N::zero(), vec![])),
+        1 => return Some((N::zero(), vec![0])),
+        _ => {}
+    }
+    let cost = |i: usize, j: usize| graph.has_arc(i, j).then(|| graph.cost(i, j));
+
+    // node 0 is the start, subsets and ends cover nodes 1..count
+    let others = count - 1;
+    let subsets = 1usize << others;
+    let mut best: Vec<Option<N>> = vec![None; subsets * others];
+    let mut parent = vec![0u8; subsets * others];
+    for last in 0..others {
+        best[(1 << last) * others + last] = cost(0, last + 1);
+    }
+    for subset in 1..subsets {
+        for last in (0..others).filter(|last| subset & (1 << last) != 0) {
+            let prev_subset = subset & !(1 << last);
+            if prev_subset == 0 {
+                continue;
+            }
+            let mut entry = None;
+            for prev in (0..others).filter(|prev| prev_subset & (1 << prev) != 0) {
+                let path = best[prev_subset * others + prev];
+                let arc = cost(prev + 1, last + 1);
+                if let (Some(path), Some(arc)) = (path, arc) {
+                    let length = path + arc;
+                    if entry.is_none_or(|(best, _)| length < best) {
+                        entry = Some((length, prev));
+                    }
+                }
+            }
+            if let Some((length, prev)) = entry {
+                best[subset * others + last] = Some(length);
+                parent[subset * others + last] = prev as u8;
+            }
+        }
+    }
+
+    let full = subsets - 1;
+    let (length, mut last) = (0..others)
+        .filter_map(|last| {
+            let path = best[full * others + last]?;
+            Some((path + cost(last + 1, 0)?, last))
+        })
+        .min_by(|a, b| compare(a.0, b.0))?;
+
+    let mut tour = Vec::with_capacity(count);
+    let mut subset = full;
+    while subset != 0 {
+        tour.push(last + 1);
+        let prev = parent[subset * others + last] as usize;
+        subset &= !(1 << last);
+        last = prev;
+    }
+    tour.push(0);
+    tour.reverse();
+    Some((length, tour))
+}
+
+/**
+ * Weights are only partially ordered: treat incomparable ones, like NaN, as equal.
+ */
+fn compare<N: PartialOrd>(a: N, b: N) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::path_cost::is_hamiltonian_cycle;
+    use crate::Graph;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_instance(count: usize, gtype: GraphType, seed: u64) -> MatrixGraph<i64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let points: Vec<(i64, i64)> = (0..count)
+            .map(|_| (rng.gen_range(0..1000), rng.gen_range(0..1000)))
+            .collect();
+        let mut graph = MatrixGraph::new(count, gtype);
+        for (i, p) in points.iter().enumerate() {
+            for (j, q) in points.iter().enumerate() {
+                if i != j {
+                    let dist = (((p.0 - q.0).pow(2) + (p.1 - q.1).pow(2)) as f64).sqrt();
+                    // make direct instances asymmetric
+                    let extra = if gtype == GraphType::Direct && i < j {
+                        50
+                    } else {
+                        0
+                    };
+                    graph.add_new_arc(i, j, dist as i64 + extra);
+                }
+            }
+        }
+        graph
+    }
+
+    fn length(graph: &MatrixGraph<i64>, tour: &[usize]) -> i64 {
+        TourCost::new(graph, tour).length()
+    }
+
+    #[test]
+    fn test_construction() {
+        let graph = random_instance(60, GraphType::Undirect, 1);
+        let tours = [
+            nearest_neighbour(&graph, 5),
+            cheapest_insertion(&graph, 5),
+            christofides(&graph),
+        ];
+        for tour in &tours {
+            assert!(is_hamiltonian_cycle(&graph, tour, 60));
+        }
+        assert_eq!(tours[0][0], 5);
+        assert_eq!(tours[1][0], 5);
+
+        // on a line the cheapest insertion is optimal
+        let mut line = MatrixGraph::<i64>::new_undirect(5);
+        for i in 0..5 {
+            for j in (i + 1)..5 {
+                line.add_new_arc(i, j, (j - i) as i64);
+            }
+        }
+        let tour = cheapest_insertion(&line, 2);
+        assert_eq!(length(&line, &tour), 8);
+        assert_eq!(length(&line, &christofides(&line)), 8);
+    }
+
+    #[test]
+    fn test_local_search() {
+        for gtype in [GraphType::Undirect, GraphType::Direct] {
+            let graph = random_instance(40, gtype, 2);
+            let initial: Vec<_> = (0..40).collect();
+
+            let mut tour = initial.clone();
+            let moves = two_opt(&graph, &mut tour, Budget::unlimited());
+            assert!(moves > 0);
+            assert!(is_hamiltonian_cycle(&graph, &tour, 40));
+            assert!(length(&graph, &tour) < length(&graph, &initial));
+            // a local optimum
+            assert_eq!(two_opt(&graph, &mut tour, Budget::unlimited()), 0);
+
+            let mut tour = initial.clone();
+            or_opt(&graph, &mut tour, Budget::unlimited());
+            assert!(is_hamiltonian_cycle(&graph, &tour, 40));
+            assert_eq!(or_opt(&graph, &mut tour, Budget::unlimited()), 0);
+
+            let mut tour = nearest_neighbour(&graph, 0);
+            let before = length(&graph, &tour);
+            local_search(&graph, &mut tour, Budget::unlimited());
+            assert!(length(&graph, &tour) <= before);
+            assert_eq!(two_opt(&graph, &mut tour, Budget::unlimited()), 0);
+            assert_eq!(or_opt(&graph, &mut tour, Budget::unlimited()), 0);
+        }
+    }
+
+    #[test]
+    fn test_budget() {
+        let graph = random_instance(40, GraphType::Undirect, 3);
+        let mut tour: Vec<_> = (0..40).collect();
+        assert_eq!(two_opt(&graph, &mut tour, Budget::moves(3)), 3);
+        assert_eq!(local_search(&graph, &mut tour, Budget::moves(5)), 5);
+        let moves = local_search(&graph, &mut tour, Budget::time(Duration::ZERO));
+        assert_eq!(moves, 0);
+    }
+
+    #[test]
+    fn test_held_karp() {
+        for gtype in [GraphType::Undirect, GraphType::Direct] {
+            let graph = random_instance(9, gtype, 4);
+            let (optimum, tour) = held_karp(&graph).unwrap();
+            assert_eq!(length(&graph, &tour), optimum);
+            assert!(is_hamiltonian_cycle(&graph, &tour, 9));
+            let mut heuristic = christofides_or_nearest(&graph);
+            local_search(&graph, &mut heuristic, Budget::unlimited());
+            assert!(length(&graph, &heuristic) >= optimum);
+        }
+
+        // a direct cycle has a single tour
+        let mut graph = MatrixGraph::<i64>::new_direct(5);
+        for i in 0..5 {
+            graph.add_new_arc(i, (i + 2) % 5, 1);
+        }
+        assert_eq!(held_karp(&graph), Some((5, vec![0, 2, 4, 1, 3])));
+        let graph = MatrixGraph::<i64>::new_direct(4);
+        assert_eq!(held_karp(&graph), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "held-karp supports at most 18 nodes")]
+    fn test_held_karp_too_large() {
+        held_karp(&MatrixGraph::<i64>::new_undirect(HELD_KARP_MAX_NODES + 1));
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let graph = MatrixGraph::<i64>::new_undirect(0);
+        assert!(nearest_neighbour(&graph, 0).is_empty());
+        assert!(cheapest_insertion(&graph, 0).is_empty());
+        assert!(christofides(&graph).is_empty());
+        assert_eq!(held_karp(&graph), Some((0, vec![])));
+    }
+
+    fn christofides_or_nearest(graph: &MatrixGraph<i64>) -> Vec<usize> {
+        match graph.graph_type() {
+            GraphType::Undirect => christofides(graph),
+            GraphType::Direct => nearest_neighbour(graph, 0),
+        }
+    }
+}
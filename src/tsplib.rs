@@ -0,0 +1,568 @@
+/*!
+ * Load symmetric (`TSP`) and asymmetric (`ATSP`) traveling salesman
+ * instances and tours in the
+ * [TSPLIB](http://comopt.ifi.uni-heidelberg.de/software/TSPLIB95/) format.
+ * Supported edge weight types are `EUC_2D`, `CEIL_2D`, `GEO`, `ATT` and
+ * `EXPLICIT` (with `FULL_MATRIX`, `UPPER_ROW`, `UPPER_DIAG_ROW`, `LOWER_ROW`
+ * and `LOWER_DIAG_ROW` formats). TSPLIB numbers nodes starting from 1
+ * while this crate starts from 0: node indexes are converted while reading.
+ */
+
+use super::read_error::ReadError;
+use super::{Graph, GraphType, MatrixGraph};
+use serde::Serialize;
+use std::io;
+
+/**
+ * A traveling salesman instance: a complete graph, direct
+ * for `ATSP` instances and undirect for `TSP` ones, and
+ * the nodes' coordinates, when the file provides them.
+ */
+#[derive(Clone)]
+pub struct TspInstance<N>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+{
+    pub name: String,
+    pub graph: MatrixGraph<N>,
+    pub coordinates: Option<Vec<(f64, f64)>>,
+}
+
+/**
+ * Distance functions defined by TSPLIB.
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EdgeWeightType {
+    Euc2D,
+    Ceil2D,
+    Geo,
+    Att,
+    Explicit,
+}
+
+impl EdgeWeightType {
+    /**
+     * Compute the distance between two points, rounded as defined by TSPLIB.
+     * Panics for [EdgeWeightType::Explicit], whose distances are not
+     * computed from coordinates.
+     */
+    pub fn distance(&self, p: (f64, f64), q: (f64, f64)) -> i64 {
+        self.rounded_distance(p, q) as i64
+    }
+
+    // The integral distance, still as a f64 so that callers can check
+    // its range before converting it
+    fn rounded_distance(&self, p: (f64, f64), q: (f64, f64)) -> f64 {
+        let dx = p.0 - q.0;
+        let dy = p.1 - q.1;
+        match self {
+            Self::Euc2D => nint((dx * dx + dy * dy).sqrt()),
+            Self::Ceil2D => (dx * dx + dy * dy).sqrt().ceil(),
+            Self::Att => {
+                let r = ((dx * dx + dy * dy) / 10.0).sqrt();
+                let t = nint(r);
+                if t < r {
+                    t + 1.0
+                } else {
+                    t
+                }
+            }
+            Self::Geo => geo_distance(p, q),
+            Self::Explicit => panic!("explicit distances are not computed from coordinates"),
+        }
+    }
+}
+
+/**
+ * Read a `TSP` or `ATSP` instance from *reader*. Weights are converted
+ * to *N* with [NumCast](num_traits::NumCast): a weight that does not
+ * fit *N* is reported as a [ReadError], like the
+ * [geometric](crate::geometric) builders return
+ * [DistanceOutOfRange](crate::geometric::DistanceOutOfRange).
+ *
+ * ```
+ * use simplegraph::tsplib::read_tsplib;
+ *
+ * let source = "NAME : square
+ * TYPE : TSP
+ * DIMENSION : 4
+ * EDGE_WEIGHT_TYPE : EUC_2D
+ * NODE_COORD_SECTION
+ * 1 0 0
+ * 2 0 3
+ * 3 4 3
+ * 4 4 0
+ * EOF
+ * ";
+ * let instance = read_tsplib::<_, u32>(source.as_bytes()).unwrap();
+ * let graph = &instance.graph;
+ * assert_eq!(graph.arc_iterator().filter(|(i, j, _)| i < j).count(), 6);
+ * assert_eq!(graph.successor_iterator(0).map(|(_, _, w)| w).collect::<Vec<_>>(), vec![3, 5, 4]);
+ * ```
+ */
+pub fn read_tsplib<R, N>(reader: R) -> Result<TspInstance<N>, ReadError>
+where
+    R: io::BufRead,
+    N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+{
+    let mut parser = Parser::new(reader);
+    let header = parser.header()?;
+    let gtype = match header.problem_type.as_str() {
+        "TSP" => GraphType::Undirect,
+        "ATSP" => GraphType::Direct,
+        other => return Err(parser.error(format!("unsupported problem type \"{other}\""))),
+    };
+    let dimension = header.dimension(&parser)?;
+    // the instance ends up in a dimension x dimension matrix
+    parser.matrix_size(dimension)?;
+    let weight_type = header.edge_weight_type(&parser)?;
+
+    let mut coordinates = None;
+    let mut coordinate_lines = vec![];
+    let mut weights = None;
+    while let Some(section) = parser.next_section()? {
+        match section.as_str() {
+            "NODE_COORD_SECTION" | "DISPLAY_DATA_SECTION" => {
+                let (points, lines) = parser.coordinates(dimension)?;
+                coordinates = Some(points);
+                coordinate_lines = lines;
+            }
+            "EDGE_WEIGHT_SECTION" => {
+                let format = header.edge_weight_format(&parser)?;
+                weights = Some(parser.explicit_weights(dimension, format)?);
+            }
+            "EOF" => break,
+            other => return Err(parser.error(format!("unsupported section \"{other}\""))),
+        }
+    }
+
+    let mut graph = MatrixGraph::new(dimension, gtype);
+    match (weight_type, &weights, &coordinates) {
+        (EdgeWeightType::Explicit, Some(weights), _) => {
+            fill_graph(&mut graph, dimension, gtype, |i, j| {
+                Ok(weights[i * dimension + j])
+            })?
+        }
+        (EdgeWeightType::Explicit, None, _) => {
+            return Err(parser.error("missing EDGE_WEIGHT_SECTION"))
+        }
+        (kind, _, Some(coordinates)) => {
+            fill_graph(&mut graph, dimension, gtype, |i, j| {
+                let d = kind.rounded_distance(coordinates[i], coordinates[j]);
+                N::from(d).ok_or_else(|| {
+                    // report the coordinates read last, they made the distance too large
+                    let line = coordinate_lines[i].max(coordinate_lines[j]);
+                    let (i, j) = (i + 1, j + 1);
+                    ReadError::syntax(line, format!("weight {d} of arc ({i}, {j}) out of range"))
+                })
+            })?
+        }
+        (_, _, None) => return Err(parser.error("missing NODE_COORD_SECTION")),
+    }
+
+    Ok(TspInstance {
+        name: header.name,
+        graph,
+        coordinates,
+    })
+}
+
+/**
+ * Read a tour (a `.tour` file) from *reader*. The result
+ * contains each node once, in visiting order, and can be passed
+ * to [AllSubPathCost::new](crate::path_cost::AllSubPathCost::new):
+ * push the first node at the end to account for the arc closing the tour.
+ *
+ * ```
+ * use simplegraph::tsplib::read_tour;
+ *
+ * let source = "NAME : square.tour\nTYPE : TOUR\nDIMENSION : 4\nTOUR_SECTION\n1\n4\n3\n2\n-1\nEOF\n";
+ * assert_eq!(read_tour(source.as_bytes()).unwrap(), vec![0, 3, 2, 1]);
+ * ```
+ */
+pub fn read_tour<R: io::BufRead>(reader: R) -> Result<Vec<usize>, ReadError> {
+    let mut parser = Parser::new(reader);
+    let header = parser.header()?;
+    if header.problem_type != "TOUR" {
+        let message = format!(
+            "expected problem type \"TOUR\", found \"{}\"",
+            header.problem_type
+        );
+        return Err(parser.error(message));
+    }
+    let dimension = header.dimension.unwrap_or(usize::MAX);
+    let mut tour = vec![];
+    match parser.next_section()?.as_deref() {
+        Some("TOUR_SECTION") => loop {
+            let token = parser.next_number::<i64>("node")?;
+            match token {
+                -1 => break,
+                n if n >= 1 && (n as usize) <= dimension => tour.push(n as usize - 1),
+                n => return Err(parser.error(format!("node {n} out of range"))),
+            }
+        },
+        _ => return Err(parser.error("missing TOUR_SECTION")),
+    }
+    if header.dimension.is_some() && tour.len() != dimension {
+        let message = format!("expected {dimension} nodes, found {}", tour.len());
+        return Err(parser.error(message));
+    }
+    Ok(tour)
+}
+
+fn fill_graph<N, F>(
+    graph: &mut MatrixGraph<N>,
+    node_count: usize,
+    gtype: GraphType,
+    f: F,
+) -> Result<(), ReadError>
+where
+    N: num_traits::Num + Default + Clone + Copy + Serialize,
+    F: Fn(usize, usize) -> Result<N, ReadError>,
+{
+    let undirect = gtype == GraphType::Undirect;
+    for i in 0..node_count {
+        for j in 0..node_count {
+            if i != j && (!undirect || i < j) {
+                graph.add_new_arc(i, j, f(i, j)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn nint(x: f64) -> f64 {
+    (x + 0.5).trunc()
+}
+
+fn geo_distance(p: (f64, f64), q: (f64, f64)) -> f64 {
+    const RRR: f64 = 6378.388;
+    let (lat_p, lon_p) = (geo_radians(p.0), geo_radians(p.1));
+    let (lat_q, lon_q) = (geo_radians(q.0), geo_radians(q.1));
+    let q1 = (lon_p - lon_q).cos();
+    let q2 = (lat_p - lat_q).cos();
+    let q3 = (lat_p + lat_q).cos();
+    (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+fn geo_radians(x: f64) -> f64 {
+    // TSPLIB defines GEO distances with this truncated value of pi
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    let deg = x.trunc();
+    let min = x - deg;
+    PI * (deg + 5.0 * min / 3.0) / 180.0
+}
+
+#[derive(Clone, Copy)]
+enum WeightFormat {
+    FullMatrix,
+    UpperRow,
+    UpperDiagRow,
+    LowerRow,
+    LowerDiagRow,
+}
+
+#[derive(Default)]
+struct Header {
+    name: String,
+    problem_type: String,
+    dimension: Option<usize>,
+    edge_weight_type: Option<String>,
+    edge_weight_format: Option<String>,
+}
+
+impl Header {
+    fn dimension<R: io::BufRead>(&self, parser: &Parser<R>) -> Result<usize, ReadError> {
+        self.dimension
+            .ok_or_else(|| parser.error("missing DIMENSION"))
+    }
+
+    fn edge_weight_type<R: io::BufRead>(
+        &self,
+        parser: &Parser<R>,
+    ) -> Result<EdgeWeightType, ReadError> {
+        match self.edge_weight_type.as_deref() {
+            Some("EUC_2D") => Ok(EdgeWeightType::Euc2D),
+            Some("CEIL_2D") => Ok(EdgeWeightType::Ceil2D),
+            Some("GEO") => Ok(EdgeWeightType::Geo),
+            Some("ATT") => Ok(EdgeWeightType::Att),
+            Some("EXPLICIT") => Ok(EdgeWeightType::Explicit),
+            Some(other) => Err(parser.error(format!("unsupported EDGE_WEIGHT_TYPE \"{other}\""))),
+            None => Err(parser.error("missing EDGE_WEIGHT_TYPE")),
+        }
+    }
+
+    fn edge_weight_format<R: io::BufRead>(
+        &self,
+        parser: &Parser<R>,
+    ) -> Result<WeightFormat, ReadError> {
+        match self.edge_weight_format.as_deref() {
+            Some("FULL_MATRIX") => Ok(WeightFormat::FullMatrix),
+            Some("UPPER_ROW") => Ok(WeightFormat::UpperRow),
+            Some("UPPER_DIAG_ROW") => Ok(WeightFormat::UpperDiagRow),
+            Some("LOWER_ROW") => Ok(WeightFormat::LowerRow),
+            Some("LOWER_DIAG_ROW") => Ok(WeightFormat::LowerDiagRow),
+            Some(other) => Err(parser.error(format!("unsupported EDGE_WEIGHT_FORMAT \"{other}\""))),
+            None => Err(parser.error("missing EDGE_WEIGHT_FORMAT")),
+        }
+    }
+}
+
+// The line each node was read from
+type Lines = Vec<usize>;
+
+struct Parser<R> {
+    lines: io::Lines<R>,
+    line_num: usize,
+    tokens: Vec<String>,
+    pending: Option<String>,
+}
+
+impl<R: io::BufRead> Parser<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_num: 0,
+            tokens: vec![],
+            pending: None,
+        }
+    }
+
+    fn next_line(&mut self) -> Result<Option<String>, ReadError> {
+        if let Some(line) = self.pending.take() {
+            return Ok(Some(line));
+        }
+        loop {
+            match self.lines.next() {
+                Some(line) => {
+                    self.line_num += 1;
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        return Ok(Some(line));
+                    }
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn header(&mut self) -> Result<Header, ReadError> {
+        let mut header = Header::default();
+        while let Some(line) = self.next_line()? {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    self.pending = Some(line);
+                    break;
+                }
+            };
+            match key {
+                "NAME" => header.name = value.to_owned(),
+                "TYPE" => header.problem_type = value.to_owned(),
+                "DIMENSION" => {
+                    let dimension = value
+                        .parse()
+                        .map_err(|_| self.error(format!("invalid DIMENSION \"{value}\"")))?;
+                    header.dimension = Some(dimension);
+                }
+                "EDGE_WEIGHT_TYPE" => header.edge_weight_type = Some(value.to_owned()),
+                "EDGE_WEIGHT_FORMAT" => header.edge_weight_format = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+        Ok(header)
+    }
+
+    fn next_section(&mut self) -> Result<Option<String>, ReadError> {
+        if let Some(token) = self.tokens.pop() {
+            return Err(self.error(format!("unexpected token \"{token}\"")));
+        }
+        Ok(self.next_line()?.map(|line| line.trim().to_owned()))
+    }
+
+    fn next_number<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, ReadError> {
+        while self.tokens.is_empty() {
+            match self.next_line()? {
+                Some(line) => {
+                    self.tokens = line.split_whitespace().rev().map(str::to_owned).collect();
+                }
+                None => return Err(self.error(format!("missing {what}"))),
+            }
+        }
+        let token = self.tokens.pop().unwrap_or_default();
+        token
+            .parse()
+            .map_err(|_| self.error(format!("invalid {what} \"{token}\"")))
+    }
+
+    fn coordinates(&mut self, dimension: usize) -> Result<(Vec<(f64, f64)>, Lines), ReadError> {
+        let mut coordinates = vec![None; dimension];
+        let mut lines = vec![0; dimension];
+        for _ in 0..dimension {
+            let node: usize = self.next_number("node")?;
+            let x = self.next_number("coordinate")?;
+            let y = self.next_number("coordinate")?;
+            if node == 0 || node > dimension {
+                return Err(self.error(format!("node {node} out of range 1..={dimension}")));
+            }
+            coordinates[node - 1] = Some((x, y));
+            lines[node - 1] = self.line_num;
+            if !self.tokens.is_empty() {
+                return Err(self.error("only two dimensional coordinates are supported"));
+            }
+        }
+        let coordinates = coordinates
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| self.error("duplicate node coordinates"))?;
+        Ok((coordinates, lines))
+    }
+
+    fn explicit_weights<N>(
+        &mut self,
+        dimension: usize,
+        format: WeightFormat,
+    ) -> Result<Vec<N>, ReadError>
+    where
+        N: num_traits::Num + num_traits::NumCast + Default + Clone + Copy + Serialize,
+    {
+        let mut weights = vec![N::zero(); self.matrix_size(dimension)?];
+        for i in 0..dimension {
+            let range = match format {
+                WeightFormat::FullMatrix => 0..dimension,
+                WeightFormat::UpperRow => (i + 1)..dimension,
+                WeightFormat::UpperDiagRow => i..dimension,
+                WeightFormat::LowerRow => 0..i,
+                WeightFormat::LowerDiagRow => 0..(i + 1),
+            };
+            for j in range {
+                let w: f64 = self.next_number("edge weight")?;
+                let w = N::from(w).ok_or_else(|| {
+                    let (i, j) = (i + 1, j + 1);
+                    self.error(format!("weight {w} of arc ({i}, {j}) out of range"))
+                })?;
+                weights[i * dimension + j] = w;
+                if !matches!(format, WeightFormat::FullMatrix) {
+                    weights[j * dimension + i] = w;
+                }
+            }
+        }
+        Ok(weights)
+    }
+
+    // The number of entries of a *dimension* x *dimension* matrix,
+    // rejecting dimensions whose matrix could not even be addressed
+    fn matrix_size(&self, dimension: usize) -> Result<usize, ReadError> {
+        dimension
+            .checked_mul(dimension)
+            .filter(|size| {
+                size.checked_mul(std::mem::size_of::<f64>())
+                    .is_some_and(|bytes| bytes <= isize::MAX as usize)
+            })
+            .ok_or_else(|| self.error(format!("DIMENSION {dimension} too large")))
+    }
+
+    fn error<S: Into<String>>(&self, message: S) -> ReadError {
+        ReadError::syntax(self.line_num, message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::path_cost::ArcCost;
+
+    #[test]
+    fn test_distances() {
+        assert_eq!(EdgeWeightType::Euc2D.distance((0.0, 0.0), (1.0, 1.0)), 1);
+        assert_eq!(EdgeWeightType::Ceil2D.distance((0.0, 0.0), (1.0, 1.0)), 2);
+        assert_eq!(EdgeWeightType::Att.distance((0.0, 0.0), (10.0, 10.0)), 5);
+        assert_eq!(EdgeWeightType::Att.distance((0.0, 0.0), (3.0, 4.0)), 2);
+        assert_eq!(
+            EdgeWeightType::Geo.distance((16.47, 96.10), (16.47, 94.44)),
+            153
+        );
+    }
+
+    #[test]
+    fn test_explicit_formats() {
+        let full = "NAME: full\nTYPE: ATSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1 2\n3 0 4\n5 6 0\nEOF\n";
+        let instance = read_tsplib::<_, u32>(full.as_bytes()).unwrap();
+        let graph = &instance.graph;
+        assert_eq!(instance.name, "full");
+        assert!(instance.coordinates.is_none());
+        assert_eq!(graph.arc_iterator().count(), 6);
+        assert_eq!(graph.cost(0, 2), 2);
+        assert_eq!(graph.cost(2, 0), 5);
+        assert_eq!(graph.cost(1, 2), 4);
+
+        let upper = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n4\n";
+        let lower = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: LOWER_DIAG_ROW\nEDGE_WEIGHT_SECTION\n0 1 0\n2 4 0\nEOF\n";
+        for source in [upper, lower] {
+            let instance = read_tsplib::<_, u32>(source.as_bytes()).unwrap();
+            let graph = &instance.graph;
+            assert_eq!(graph.arc_iterator().count(), 6);
+            assert_eq!((graph.cost(0, 1), graph.cost(1, 0)), (1, 1));
+            assert_eq!((graph.cost(0, 2), graph.cost(2, 0)), (2, 2));
+            assert_eq!((graph.cost(1, 2), graph.cost(2, 1)), (4, 4));
+        }
+    }
+
+    #[test]
+    fn test_coordinates() {
+        let source = "NAME : tri\nTYPE : TSP\nDIMENSION : 3\nEDGE_WEIGHT_TYPE : CEIL_2D\nNODE_COORD_SECTION\n3 1.5 0\n1 0 0\n2 0 2.5\nEOF\n";
+        let instance = read_tsplib::<_, f64>(source.as_bytes()).unwrap();
+        let expect = vec![(0.0, 0.0), (0.0, 2.5), (1.5, 0.0)];
+        assert_eq!(instance.coordinates, Some(expect));
+        let graph = &instance.graph;
+        assert_eq!(graph.cost(0, 1), 3.0);
+        assert_eq!(graph.cost(2, 0), 2.0);
+        assert_eq!(graph.cost(1, 2), 3.0);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let source = "TYPE : TSP\nDIMENSION : 2\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n1 0 0\n3 1 1\n";
+        let err = read_tsplib::<_, u32>(source.as_bytes()).err().unwrap();
+        assert_eq!(err.to_string(), "line 6: node 3 out of range 1..=2");
+
+        let source = "TYPE : TSP\nDIMENSION : 2\nEDGE_WEIGHT_TYPE : EUC_3D\n";
+        let err = read_tsplib::<_, u32>(source.as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 3: unsupported EDGE_WEIGHT_TYPE \"EUC_3D\""
+        );
+
+        let source = "TYPE : TSP\nDIMENSION : 2\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 0 1000\nEOF\n";
+        let err = read_tsplib::<_, u8>(source.as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 6: weight 1000 of arc (1, 2) out of range"
+        );
+        assert!(read_tsplib::<_, u16>(source.as_bytes()).is_ok());
+
+        let source = "TYPE : TSP\nDIMENSION : 3\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 0 1e30\n3 0 1\nEOF\n";
+        let err = read_tsplib::<_, i64>(source.as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 6: weight 1000000000000000000000000000000 of arc (1, 2) out of range"
+        );
+
+        let source = "TYPE : ATSP\nDIMENSION : 2\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1\n300 0\nEOF\n";
+        let err = read_tsplib::<_, u8>(source.as_bytes()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 7: weight 300 of arc (2, 1) out of range"
+        );
+
+        let source = "TYPE : ATSP\nDIMENSION : 5000000000\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0\nEOF\n";
+        let err = read_tsplib::<_, u32>(source.as_bytes()).err().unwrap();
+        assert_eq!(err.to_string(), "line 5: DIMENSION 5000000000 too large");
+
+        let source = "TYPE : TOUR\nDIMENSION : 3\nTOUR_SECTION\n1 2\n-1\n";
+        let err = read_tour(source.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 5: expected 3 nodes, found 2");
+    }
+}
@@ -0,0 +1,468 @@
+/*!
+ * Lightweight read only views over a graph. A view wraps
+ * any [GraphVisitor](visitor::GraphVisitor), like `&AdjList` or
+ * `&MatrixGraph`, without copying it, and can be passed by reference
+ * wherever a graph is expected, for example to
+ * [to_dot_source](crate::dot::to_dot_source) or to
+ * [AllSubPathCost](crate::path_cost::AllSubPathCost).
+ * Views can be nested: a view of a view is still a view.
+ *
+ * ```
+ * use simplegraph::{Graph, GraphVisitor, MatrixGraph};
+ * use simplegraph::views::{InducedSubgraph, Reversed};
+ *
+ * let mut graph = MatrixGraph::<u32>::new_direct(4);
+ * graph.add_new_arc(0, 1, 1);
+ * graph.add_new_arc(1, 2, 2);
+ * graph.add_new_arc(2, 3, 3);
+ *
+ * let sub = InducedSubgraph::new(&graph, &[3, 2, 1]);
+ * let reversed = Reversed::new(&sub);
+ * let mut arcs = vec![];
+ * (&reversed).arc_visitor(|i, j, w| arcs.push((i, j, w)));
+ * assert_eq!(arcs, vec![(1, 2, 2), (0, 1, 3)]);
+ * ```
+ */
+
+use super::path_cost::{ArcCost, HasArc};
+use super::visitor::GraphVisitor;
+use super::{GetGraphType, GraphType};
+use std::marker::PhantomData;
+
+/**
+ * View the graph with each arc reversed: the arc (*i*, *j*)
+ * becomes (*j*, *i*). Undirect graphs are unchanged. Arcs are visited
+ * in the order of the wrapped graph, so they are grouped by destination
+ * rather than by source.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Reversed<G> {
+    graph: G,
+}
+
+impl<G> Reversed<G> {
+    pub fn new(graph: G) -> Self {
+        Self { graph }
+    }
+}
+
+impl<G: GetGraphType> GetGraphType for &Reversed<G> {
+    fn graph_type(&self) -> GraphType {
+        self.graph.graph_type()
+    }
+}
+
+impl<G, N> GraphVisitor<N> for &Reversed<G>
+where
+    G: GraphVisitor<N>,
+    N: Copy,
+{
+    fn node_visitor<F: FnMut(usize, N)>(&self, f: F) {
+        self.graph.node_visitor(f)
+    }
+
+    fn arc_visitor<F: FnMut(usize, usize, N)>(&self, mut g: F) {
+        self.graph.arc_visitor(|i, j, w| g(j, i, w))
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    fn arc_count(&self) -> usize {
+        self.graph.arc_count()
+    }
+}
+
+impl<G, N> ArcCost<N> for &Reversed<G>
+where
+    G: ArcCost<N>,
+    N: num_traits::Num,
+{
+    fn cost(&self, src: usize, dst: usize) -> N {
+        self.graph.cost(dst, src)
+    }
+}
+
+impl<G: HasArc> HasArc for &Reversed<G> {
+    fn has_arc(&self, src: usize, dst: usize) -> bool {
+        self.graph.has_arc(dst, src)
+    }
+}
+
+/**
+ * View only the nodes and arcs accepted by the given predicates.
+ * A node is visible when *node_filter* returns true for its index and an arc
+ * is visible when both its ends are visible and *arc_filter* returns true for
+ * its source, destination and weight. Both predicates receive the original
+ * indexes, while visible nodes are renumbered in order, as in
+ * [InducedSubgraph]: the *k*-th visible node becomes node *k*.
+ *
+ * The [ArcCost] of a filtered out arc panics.
+ */
+#[derive(Clone, Debug)]
+pub struct Filtered<G, N, AF> {
+    sub: InducedSubgraph<G>,
+    arc_filter: AF,
+    weight: PhantomData<N>,
+}
+
+impl<G, N, AF> Filtered<G, N, AF>
+where
+    G: GraphVisitor<N>,
+    N: Copy,
+    AF: Fn(usize, usize, N) -> bool,
+{
+    pub fn new<NF: Fn(usize) -> bool>(graph: G, node_filter: NF, arc_filter: AF) -> Self {
+        let nodes: Vec<usize> = (0..graph.node_count())
+            .filter(|i| node_filter(*i))
+            .collect();
+        Self {
+            sub: InducedSubgraph::new(graph, &nodes),
+            arc_filter,
+            weight: PhantomData,
+        }
+    }
+
+    /**
+     * Return the index in the original graph of
+     * the given visible node.
+     */
+    pub fn original_index(&self, node: usize) -> usize {
+        self.sub.original_index(node)
+    }
+
+    /**
+     * Return the index in the view of the given original
+     * node, or ```None``` if it is filtered out.
+     */
+    pub fn view_index(&self, node: usize) -> Option<usize> {
+        self.sub.subgraph_index(node)
+    }
+}
+
+impl<G: GetGraphType, N, AF> GetGraphType for &Filtered<G, N, AF> {
+    fn graph_type(&self) -> GraphType {
+        (&self.sub).graph_type()
+    }
+}
+
+impl<G, N, AF> GraphVisitor<N> for &Filtered<G, N, AF>
+where
+    G: GraphVisitor<N>,
+    N: Copy,
+    AF: Fn(usize, usize, N) -> bool,
+{
+    fn node_visitor<F: FnMut(usize, N)>(&self, f: F) {
+        (&self.sub).node_visitor(f)
+    }
+
+    fn arc_visitor<F: FnMut(usize, usize, N)>(&self, mut g: F) {
+        let sub = &self.sub;
+        sub.graph.arc_visitor(|i, j, w| {
+            if let (Some(k), Some(l)) = (sub.index[i], sub.index[j]) {
+                if (self.arc_filter)(i, j, w) {
+                    g(k, l, w)
+                }
+            }
+        })
+    }
+
+    fn node_count(&self) -> usize {
+        (&self.sub).node_count()
+    }
+
+    fn arc_count(&self) -> usize {
+        let mut count = 0;
+        self.arc_visitor(|_, _, _| count += 1);
+        count
+    }
+}
+
+impl<G, N, AF> ArcCost<N> for &Filtered<G, N, AF>
+where
+    G: ArcCost<N>,
+    N: num_traits::Num + Copy,
+    AF: Fn(usize, usize, N) -> bool,
+{
+    fn cost(&self, src: usize, dst: usize) -> N {
+        let (i, j) = (self.sub.nodes[src], self.sub.nodes[dst]);
+        let weight = self.sub.graph.cost(i, j);
+        assert!(
+            (self.arc_filter)(i, j, weight),
+            "arc ({src}, {dst}) is filtered out"
+        );
+        weight
+    }
+}
+
+impl<G, N, AF> HasArc for &Filtered<G, N, AF>
+where
+    G: HasArc + ArcCost<N>,
+    N: num_traits::Num + Copy,
+    AF: Fn(usize, usize, N) -> bool,
+{
+    fn has_arc(&self, src: usize, dst: usize) -> bool {
+        let (i, j) = (self.sub.nodes[src], self.sub.nodes[dst]);
+        self.sub.graph.has_arc(i, j) && (self.arc_filter)(i, j, self.sub.graph.cost(i, j))
+    }
+}
+
+/**
+ * View the subgraph induced by a set of nodes: the given nodes
+ * and all the arcs between them. The *k*-th node in the set
+ * becomes node *k* in the subgraph. Arcs are visited in the
+ * order of the wrapped graph, so they may not be sorted by the
+ * subgraph indexes.
+ */
+#[derive(Clone, Debug)]
+pub struct InducedSubgraph<G> {
+    graph: G,
+    nodes: Vec<usize>,
+    index: Vec<Option<usize>>,
+    arc_count: usize,
+}
+
+impl<G> InducedSubgraph<G> {
+    /**
+     * Create the subgraph induced by *nodes*. Repeated
+     * nodes are taken only at their first occurrence.
+     *
+     * # Panics
+     * Panics if a node of *nodes* is not a node of *graph*.
+     */
+    pub fn new<N>(graph: G, nodes: &[usize]) -> Self
+    where
+        G: GraphVisitor<N>,
+        N: Copy,
+    {
+        let count = graph.node_count();
+        let mut index = vec![None; count];
+        let mut unique = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            assert!(*node < count, "node {node} out of range 0..{count}");
+            if index[*node].is_none() {
+                index[*node] = Some(unique.len());
+                unique.push(*node);
+            }
+        }
+        let mut arc_count = 0;
+        graph.arc_visitor(|i, j, _| {
+            if index[i].is_some() && index[j].is_some() {
+                arc_count += 1;
+            }
+        });
+        Self {
+            graph,
+            nodes: unique,
+            index,
+            arc_count,
+        }
+    }
+
+    /**
+     * Return the index in the original graph of
+     * the given subgraph node.
+     */
+    pub fn original_index(&self, node: usize) -> usize {
+        self.nodes[node]
+    }
+
+    /**
+     * Return the index in the subgraph of the given original
+     * node, or ```None``` if it is not part of the subgraph.
+     */
+    pub fn subgraph_index(&self, node: usize) -> Option<usize> {
+        self.index.get(node).copied().flatten()
+    }
+}
+
+impl<G: GetGraphType> GetGraphType for &InducedSubgraph<G> {
+    fn graph_type(&self) -> GraphType {
+        self.graph.graph_type()
+    }
+}
+
+impl<G, N> GraphVisitor<N> for &InducedSubgraph<G>
+where
+    G: GraphVisitor<N>,
+    N: Copy,
+{
+    fn node_visitor<F: FnMut(usize, N)>(&self, mut f: F) {
+        let mut weights = vec![None; self.nodes.len()];
+        self.graph.node_visitor(|i, n| {
+            if let Some(k) = self.index[i] {
+                weights[k] = Some(n);
+            }
+        });
+        for (k, n) in weights.into_iter().enumerate() {
+            if let Some(n) = n {
+                f(k, n)
+            }
+        }
+    }
+
+    fn arc_visitor<F: FnMut(usize, usize, N)>(&self, mut g: F) {
+        self.graph.arc_visitor(|i, j, w| {
+            if let (Some(i), Some(j)) = (self.index[i], self.index[j]) {
+                g(i, j, w)
+            }
+        })
+    }
+
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn arc_count(&self) -> usize {
+        self.arc_count
+    }
+}
+
+impl<G, N> ArcCost<N> for &InducedSubgraph<G>
+where
+    G: ArcCost<N>,
+    N: num_traits::Num,
+{
+    fn cost(&self, src: usize, dst: usize) -> N {
+        self.graph.cost(self.nodes[src], self.nodes[dst])
+    }
+}
+
+impl<G: HasArc> HasArc for &InducedSubgraph<G> {
+    fn has_arc(&self, src: usize, dst: usize) -> bool {
+        self.graph.has_arc(self.nodes[src], self.nodes[dst])
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::dot::to_dot_source;
+    use crate::path_cost::{path_cost, AllSubPathCost, MissingArc};
+    use crate::transitive::transitive_closure;
+    use crate::{AdjList, Graph, MatrixGraph};
+
+    fn make_graph() -> AdjList<u32> {
+        let mut graph = AdjList::new_direct(5);
+        graph.update_all_nodes_weight(|i, _| 10 * i as u32);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 3);
+        graph.add_new_arc(3, 4, 4);
+        graph.add_new_arc(4, 0, 5);
+        graph.add_new_arc(0, 2, 6);
+        graph
+    }
+
+    fn arcs<G: GraphVisitor<u32>>(g: G) -> Vec<(usize, usize, u32)> {
+        let mut arcs = vec![];
+        g.arc_visitor(|i, j, w| arcs.push((i, j, w)));
+        arcs
+    }
+
+    #[test]
+    fn test_reversed() {
+        let graph = make_graph();
+        let reversed = Reversed::new(&graph);
+        // arcs follow the wrapped graph order
+        let expect = vec![
+            (1, 0, 1),
+            (2, 0, 6),
+            (2, 1, 2),
+            (3, 2, 3),
+            (4, 3, 4),
+            (0, 4, 5),
+        ];
+        assert_eq!(arcs(&reversed), expect);
+        assert_eq!((&reversed).arc_count(), 6);
+        assert_eq!((&reversed).cost(1, 0), 1);
+
+        let mut path_cost = AllSubPathCost::new(&reversed, &[3, 2, 1]);
+        assert_eq!(path_cost.next(), Some((3, 2, 3)));
+        assert_eq!(path_cost.next(), Some((3, 1, 5)));
+
+        let twice = Reversed::new(&reversed);
+        assert_eq!(arcs(&twice), arcs(&graph));
+    }
+
+    #[test]
+    fn test_filtered() {
+        let graph = make_graph();
+        let filtered = Filtered::new(&graph, |i| i != 3, |_, _, w: u32| w > 1);
+        assert_eq!(arcs(&filtered), vec![(0, 2, 6), (1, 2, 2), (3, 0, 5)]);
+        assert_eq!((&filtered).node_count(), 4);
+        assert_eq!((&filtered).arc_count(), 3);
+        assert_eq!(filtered.original_index(3), 4);
+        assert_eq!(filtered.view_index(3), None);
+        let mut nodes = vec![];
+        (&filtered).node_visitor(|i, n| nodes.push((i, n)));
+        assert_eq!(nodes, vec![(0, 0), (1, 10), (2, 20), (3, 40)]);
+        assert_eq!((&filtered).cost(3, 0), 5);
+        assert!((&filtered).has_arc(0, 2));
+        assert!(!(&filtered).has_arc(0, 1));
+        assert_eq!(path_cost(&filtered, &[1, 2]), Ok(2));
+        assert_eq!(
+            path_cost(&filtered, &[0, 1, 2]),
+            Err(MissingArc { src: 0, dst: 1 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "arc (0, 1) is filtered out")]
+    fn test_filtered_cost() {
+        let graph = make_graph();
+        let filtered = Filtered::new(&graph, |_| true, |_, _, w: u32| w > 1);
+        (&filtered).cost(0, 1);
+    }
+
+    #[test]
+    fn test_filtered_closure() {
+        let graph = make_graph();
+        let filtered = Filtered::new(&graph, |i| i != 0, |_, _, _| true);
+        let closure = transitive_closure(&filtered);
+        assert_eq!((&closure).node_count(), 4);
+        let mut reached: Vec<_> = closure.arc_iterator().map(|(i, j, _)| (i, j)).collect();
+        reached.sort();
+        // 1 -> 2 -> 3 -> 4, the arcs through node 0 are hidden
+        assert_eq!(
+            reached,
+            vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "node 5 out of range 0..5")]
+    fn test_induced_subgraph_out_of_range() {
+        let graph = make_graph();
+        InducedSubgraph::new(&graph, &[0, 5]);
+    }
+
+    #[test]
+    fn test_induced_subgraph() {
+        let mut graph = MatrixGraph::new_undirect(5);
+        graph.update_all_nodes_weight(|i, _| i as u32);
+        graph.add_new_arc(0, 1, 1);
+        graph.add_new_arc(1, 2, 2);
+        graph.add_new_arc(2, 3, 3);
+        graph.add_new_arc(3, 4, 4);
+
+        let sub = InducedSubgraph::new(&graph, &[3, 1, 2, 3]);
+        assert_eq!((&sub).node_count(), 3);
+        assert_eq!((&sub).arc_count(), 4);
+        assert_eq!((&sub).graph_type(), GraphType::Undirect);
+        assert_eq!(sub.original_index(0), 3);
+        assert_eq!(sub.subgraph_index(2), Some(2));
+        assert_eq!(sub.subgraph_index(4), None);
+        assert_eq!(arcs(&sub), vec![(1, 2, 2), (2, 1, 2), (2, 0, 3), (0, 2, 3)]);
+        assert_eq!((&sub).cost(0, 2), 3);
+
+        let mut nodes = vec![];
+        (&sub).node_visitor(|i, n| nodes.push((i, n)));
+        assert_eq!(nodes, vec![(0, 3), (1, 1), (2, 2)]);
+
+        let expect = "graph {\n\tn0 [label=\"3\"];\n\tn1 [label=\"1\"];\n\tn2 [label=\"2\"];\n\tn1 -- n2 [label=\"2\"];\n\tn0 -- n2 [label=\"3\"];\n}";
+        assert_eq!(to_dot_source(&sub), expect);
+    }
+}
@@ -41,3 +41,19 @@ where
         self.arc_count() + self.node_count()
     }
 }
+
+/**
+ * Visit the arcs exiting a node, for algorithms
+ * that explore a graph one node at a time.
+ */
+pub trait SuccessorVisitor<N>
+where
+    N: Copy,
+{
+    /**
+     * Call function *f* for each arc exiting *node*.
+     * At each call the first argument is the destination
+     * node index and the second the current arc weight.
+     */
+    fn successor_visitor<F: FnMut(usize, N)>(&self, node: usize, f: F);
+}
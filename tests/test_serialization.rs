@@ -1,4 +1,3 @@
-use simplegraph;
 use simplegraph::Graph;
 
 #[test]
@@ -31,3 +30,97 @@ where
 
     graph
 }
+
+#[test]
+fn test_compact_undirect_arcs() {
+    let graph = make_undirect_graph(simplegraph::AdjList::new_undirect);
+    let graph_json = serde_json::to_value(&graph).unwrap();
+    let expect = serde_json::json!({"Weighted": [[0, 1, 1], [1, 2, 2], [2, 3, 3]]});
+    assert_eq!(graph_json["arcs"], expect);
+
+    let mut simple_graph = simplegraph::AdjList::<usize>::new_undirect(3);
+    simple_graph.add_new_default_arc(0, 1);
+    simple_graph.add_new_default_arc(2, 1);
+    let graph_json = serde_json::to_value(&simple_graph).unwrap();
+    let expect = serde_json::json!({"Simple": [[0, 1], [1, 2]]});
+    assert_eq!(graph_json["arcs"], expect);
+}
+
+#[test]
+fn test_load_both_arc_directions() {
+    let graph_json = r#"{
+        "gtype": "Undirect",
+        "nodes": {"Extended": [0, 1, 2, 3]},
+        "arcs": {"Weighted": [[0, 1, 1], [1, 0, 1], [1, 2, 2], [2, 1, 2], [2, 3, 3], [3, 2, 3]]}
+    }"#;
+    let graph: simplegraph::AdjList<usize> = serde_json::from_str(graph_json).unwrap();
+    let expect = make_undirect_graph(simplegraph::AdjList::new_undirect);
+    assert_eq!(graph, expect);
+}
+
+#[test]
+fn test_reject_invalid_graph() {
+    let graph_json = r#"{
+        "gtype": "Direct",
+        "nodes": {"Extended": [0, 1, 2]},
+        "arcs": {"Simple": [[0, 1], [1, 3]]}
+    }"#;
+    let err = serde_json::from_str::<simplegraph::AdjList<usize>>(graph_json).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("arc (1, 3) out of range: the graph has 3 nodes"));
+
+    let graph_json = r#"{
+        "gtype": "Undirect",
+        "nodes": {"Compact": {"count": 2, "weights": [[2, 1]]}},
+        "arcs": {"Simple": []}
+    }"#;
+    let err = serde_json::from_str::<simplegraph::MatrixGraph<usize>>(graph_json)
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .starts_with("node 2 out of range: the graph has 2 nodes"));
+}
+
+#[test]
+fn test_header() {
+    use simplegraph::math_graph::{MathGraph, Representation, FORMAT_VERSION};
+
+    let graph = make_undirect_graph(simplegraph::AdjList::new_undirect);
+    let graph_json = serde_json::to_value(&graph).unwrap();
+    let expect = serde_json::json!({"version": FORMAT_VERSION, "representation": "AdjList"});
+    assert_eq!(graph_json["header"], expect);
+
+    let math_graph = MathGraph::from(graph.clone())
+        .with_name("path")
+        .with_attribute("origin", "test");
+    let graph_json = serde_json::to_string(&math_graph).unwrap();
+    let math_graph: MathGraph<usize> = serde_json::from_str(&graph_json).unwrap();
+    assert_eq!(math_graph.version(), FORMAT_VERSION);
+    assert_eq!(math_graph.representation(), Some(Representation::AdjList));
+    assert_eq!(math_graph.name(), Some("path"));
+    assert_eq!(math_graph.attribute("origin"), Some("test"));
+    assert_eq!(simplegraph::AdjList::from(math_graph), graph);
+
+    let legacy_json = r#"{
+        "gtype": "Undirect",
+        "nodes": {"Extended": [0, 1, 2, 3]},
+        "arcs": {"Weighted": [[0, 1, 1], [1, 2, 2], [2, 3, 3]]}
+    }"#;
+    let math_graph: MathGraph<usize> = serde_json::from_str(legacy_json).unwrap();
+    assert!(math_graph.header().is_none());
+    assert_eq!(math_graph.version(), 0);
+    assert_eq!(simplegraph::AdjList::from(math_graph), graph);
+
+    let future_json = r#"{
+        "header": {"version": 99},
+        "gtype": "Direct",
+        "nodes": {"Extended": [0]},
+        "arcs": {"Simple": []}
+    }"#;
+    let err = serde_json::from_str::<MathGraph<usize>>(future_json)
+        .err()
+        .unwrap();
+    assert!(err.to_string().starts_with("unsupported format version 99"));
+}
@@ -0,0 +1,97 @@
+use simplegraph::path_cost::{is_hamiltonian_cycle, TourCost};
+use simplegraph::tsp::{
+    cheapest_insertion, christofides, held_karp, local_search, nearest_neighbour, Budget,
+};
+use simplegraph::tsplib::{read_tsplib, TspInstance};
+
+const BURMA14: &str = "NAME: burma14
+TYPE: TSP
+COMMENT: 14-Staedte in Burma (Zaw Win)
+DIMENSION: 14
+EDGE_WEIGHT_TYPE: GEO
+EDGE_WEIGHT_FORMAT: FUNCTION
+DISPLAY_DATA_TYPE: COORD_DISPLAY
+NODE_COORD_SECTION
+   1  16.47       96.10
+   2  16.47       94.44
+   3  20.09       92.54
+   4  22.39       93.37
+   5  25.23       97.24
+   6  22.00       96.05
+   7  20.47       97.02
+   8  17.20       96.29
+   9  16.30       97.38
+  10  14.05       98.12
+  11  16.53       97.38
+  12  21.52       95.59
+  13  19.41       97.13
+  14  20.09       94.55
+EOF
+";
+
+const ULYSSES16: &str = "NAME: ulysses16.tsp
+TYPE: TSP
+COMMENT: Odyssey of Ulysses (Groetschel/Padberg)
+DIMENSION: 16
+EDGE_WEIGHT_TYPE: GEO
+DISPLAY_DATA_TYPE: COORD_DISPLAY
+NODE_COORD_SECTION
+ 1 38.24 20.42
+ 2 39.57 26.15
+ 3 40.56 25.32
+ 4 36.26 23.12
+ 5 33.48 10.54
+ 6 37.56 12.19
+ 7 38.42 13.11
+ 8 37.52 20.44
+ 9 41.23 9.10
+ 10 41.17 13.05
+ 11 36.08 -5.21
+ 12 38.47 15.13
+ 13 38.15 15.35
+ 14 37.51 15.17
+ 15 35.49 14.32
+ 16 39.36 19.56
+EOF
+";
+
+fn load(source: &str) -> TspInstance<i64> {
+    read_tsplib(source.as_bytes()).unwrap()
+}
+
+fn check_instance(source: &str, optimum: i64) {
+    let instance = load(source);
+    let graph = &instance.graph;
+    let count = graph.node_iterator().count();
+
+    let (length, tour) = held_karp(graph).unwrap();
+    assert_eq!(length, optimum);
+    assert_eq!(TourCost::new(graph, &tour).length(), optimum);
+    assert!(is_hamiltonian_cycle(graph, &tour, count));
+
+    let tours = [
+        nearest_neighbour(graph, 0),
+        cheapest_insertion(graph, 0),
+        christofides(graph),
+    ];
+    for mut tour in tours {
+        assert!(is_hamiltonian_cycle(graph, &tour, count));
+        let before = TourCost::new(graph, &tour).length();
+        local_search(graph, &mut tour, Budget::unlimited());
+        let after = TourCost::new(graph, &tour).length();
+        assert!(is_hamiltonian_cycle(graph, &tour, count));
+        assert!(optimum <= after && after <= before);
+        // local search gets within 10% of the optimum on these instances
+        assert!(after * 10 <= optimum * 11, "{after} vs {optimum}");
+    }
+}
+
+#[test]
+fn test_burma14() {
+    check_instance(BURMA14, 3323);
+}
+
+#[test]
+fn test_ulysses16() {
+    check_instance(ULYSSES16, 6859);
+}